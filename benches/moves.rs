@@ -0,0 +1,50 @@
+//! How long a move takes on the usual board and with the lookup tables. Criterion can't be
+//! fetched here, so this times the moves itself with `Instant` and prints the results.
+
+use play_2048::{simulation::reachable_board, Board, Move};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// How many boards to make each move on.
+const BOARDS: u64 = 1000;
+
+/// How many times to go through all of the boards, taking the fastest.
+const ROUNDS: u32 = 20;
+
+/// The fastest time, out of [ROUNDS], that `apply` takes to make every move on every board.
+fn time(boards: &[Board], apply: impl Fn(Board, Move) -> Board) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            for &board in boards {
+                for direction in Move::all() {
+                    black_box(apply(black_box(board), direction));
+                }
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() {
+    let boards = (0..BOARDS)
+        .map(|case| reachable_board(&mut StdRng::seed_from_u64(case), (case % 300) as u32))
+        .collect::<Vec<_>>();
+    let moves = boards.len() as u32 * Move::all().len() as u32;
+
+    // Build the tables before anything is timed
+    boards[0].apply_move_fast(Move::Left);
+    let usual = time(&boards, Board::apply_move);
+    let fast = time(&boards, Board::apply_move_fast);
+
+    println!("apply_move       {:>8.1?} a move", usual / moves);
+    println!("apply_move_fast  {:>8.1?} a move", fast / moves);
+    println!(
+        "speedup          {:>8.1}x",
+        usual.as_secs_f64() / fast.as_secs_f64()
+    );
+}
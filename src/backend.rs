@@ -0,0 +1,325 @@
+//! Where the [Renderer](crate::Renderer) draws the game: a real terminal, or a grid of text in
+//! memory for checking what would be on the screen.
+//!
+//! Rows are counted from the top of the rows the game has to itself, and columns from the left
+//! edge of the screen.
+
+use crate::TileStyle;
+use crossterm::QueueableCommand;
+use std::{cmp::Ordering, io, io::Write, time::Duration};
+
+/// How long the screen stays inverted when it flashes
+const FLASH_DURATION: Duration = Duration::from_millis(100);
+
+/// Something the game can be drawn on. Drawing may be held back until [Backend::flush].
+pub trait Backend {
+    /// How big the screen is, as columns and rows.
+    fn size(&self) -> io::Result<(u16, u16)>;
+
+    /// Make sure the game has at least `rows` rows to itself, scrolling whatever is above them up
+    /// to make room.
+    fn reserve_rows(&mut self, rows: u16) -> io::Result<()>;
+
+    /// Blank the whole screen, and make its top row the top of the game's rows.
+    fn clear_screen(&mut self) -> io::Result<()>;
+
+    /// Blank a row from `column` to the right edge of the screen.
+    fn clear_line(&mut self, row: u16, column: u16) -> io::Result<()>;
+
+    /// Write plain text, starting at `row` and `column`.
+    fn draw_text(&mut self, row: u16, column: u16, text: &str) -> io::Result<()>;
+
+    /// Draw a tile's text, already padded out to the width of a square, in the tile's style.
+    fn draw_cell(&mut self, row: u16, column: u16, text: &str, style: TileStyle) -> io::Result<()>;
+
+    /// Blank a square `width` columns wide.
+    fn clear_cell(&mut self, row: u16, column: u16, width: u16) -> io::Result<()> {
+        self.draw_text(row, column, &" ".repeat(width.into()))
+    }
+
+    /// Leave the cursor at `row` and `column`, out of the way of the game, for screens that can't
+    /// hide it.
+    fn park_cursor(&mut self, row: u16, column: u16) -> io::Result<()>;
+
+    /// Ring the bell. It goes out along with whatever is drawn next.
+    fn bell(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Flash the whole screen for a moment.
+    fn flash(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Put everything drawn so far on the screen.
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// A terminal, driven with crossterm. The terminal is in raw mode, with the mouse reported and
+/// held keys told apart from presses where the terminal can, until the backend is dropped.
+pub struct CrosstermBackend<Output: Write> {
+    output: Output,
+    /// The row the cursor is on
+    cursor_row: u16,
+    /// How many rows the game has made for itself at the bottom of the screen
+    rows: u16,
+    /// Whether the terminal has been asked to report held keys as repeats, which has to be undone
+    reports_repeats: bool,
+}
+
+/// A grid of text in memory, for tests and anything else that wants to see what would be on the
+/// screen. Colours and other styles are left out.
+#[derive(Clone, Debug)]
+pub struct StringBackend {
+    lines: Vec<Vec<char>>,
+    columns: u16,
+    /// Where the cursor was last parked, as a row and column
+    cursor: Option<(u16, u16)>,
+    /// Whether anything has been drawn past the edges of the screen, and cut off
+    drew_off_screen: bool,
+}
+
+impl<Output: Write> CrosstermBackend<Output> {
+    /// Take over the terminal that `output` writes to. The cursor is hidden, although there's no
+    /// telling whether the terminal actually does it, as the request is only written out.
+    pub fn new(mut output: Output) -> io::Result<Self> {
+        crossterm::terminal::enable_raw_mode()?;
+        output.queue(crossterm::cursor::Hide)?;
+        // For swiping the tiles around, on terminals that report the mouse
+        output.queue(crossterm::event::EnableMouseCapture)?;
+        // Without this, a held key comes through as one press after another, and can't be slowed
+        // down. Terminals that can't say whether they support it are left as they are.
+        let reports_repeats = crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if reports_repeats {
+            output.queue(crossterm::event::PushKeyboardEnhancementFlags(
+                crossterm::event::KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+            ))?;
+        }
+        Ok(CrosstermBackend {
+            output,
+            cursor_row: 0,
+            rows: 0,
+            reports_repeats,
+        })
+    }
+
+    /// Move the cursor to the given row. Moves are relative, as the game's rows are wherever the
+    /// bottom of the screen was when it started.
+    fn move_to_row(&mut self, row: u16) -> io::Result<()> {
+        match row.cmp(&self.cursor_row) {
+            Ordering::Less => self
+                .output
+                .queue(crossterm::cursor::MoveUp(self.cursor_row - row))?,
+            Ordering::Equal => &mut self.output,
+            Ordering::Greater => self
+                .output
+                .queue(crossterm::cursor::MoveDown(row - self.cursor_row))?,
+        };
+        self.cursor_row = row;
+        Ok(())
+    }
+
+    fn move_to(&mut self, row: u16, column: u16) -> io::Result<()> {
+        self.move_to_row(row)?;
+        self.output.queue(crossterm::cursor::MoveToColumn(column))?;
+        Ok(())
+    }
+}
+
+impl<Output: Write> Backend for CrosstermBackend<Output> {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        crossterm::terminal::size()
+    }
+
+    fn reserve_rows(&mut self, rows: u16) -> io::Result<()> {
+        if rows <= self.rows {
+            return Ok(());
+        }
+        if self.rows > 0 {
+            self.move_to_row(self.rows - 1)?;
+        }
+        for _ in self.rows..rows {
+            write!(self.output, "\r\n")?;
+        }
+        self.rows = rows;
+        self.cursor_row = rows - 1;
+        Ok(())
+    }
+
+    fn clear_screen(&mut self) -> io::Result<()> {
+        self.output
+            .queue(crossterm::terminal::Clear(
+                crossterm::terminal::ClearType::All,
+            ))?
+            .queue(crossterm::cursor::MoveTo(0, 0))?;
+        self.cursor_row = 0;
+        Ok(())
+    }
+
+    fn clear_line(&mut self, row: u16, column: u16) -> io::Result<()> {
+        self.move_to(row, column)?;
+        self.output.queue(crossterm::terminal::Clear(
+            crossterm::terminal::ClearType::UntilNewLine,
+        ))?;
+        Ok(())
+    }
+
+    fn draw_text(&mut self, row: u16, column: u16, text: &str) -> io::Result<()> {
+        self.move_to(row, column)?;
+        write!(self.output, "{}", text)
+    }
+
+    fn draw_cell(&mut self, row: u16, column: u16, text: &str, style: TileStyle) -> io::Result<()> {
+        self.move_to(row, column)?;
+        if let Some(background) = style.background {
+            self.output
+                .queue(crossterm::style::SetBackgroundColor(background))?;
+        }
+        if let Some(foreground) = style.foreground {
+            self.output
+                .queue(crossterm::style::SetForegroundColor(foreground))?;
+        }
+        if style.bold {
+            self.output.queue(crossterm::style::SetAttribute(
+                crossterm::style::Attribute::Bold,
+            ))?;
+        }
+        if style.underline {
+            self.output.queue(crossterm::style::SetAttribute(
+                crossterm::style::Attribute::Underlined,
+            ))?;
+        }
+        write!(self.output, "{}", text)?;
+        if style.background.is_some() || style.foreground.is_some() {
+            self.output.queue(crossterm::style::ResetColor)?;
+        } else if style.bold || style.underline {
+            self.output.queue(crossterm::style::SetAttribute(
+                crossterm::style::Attribute::Reset,
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn park_cursor(&mut self, row: u16, column: u16) -> io::Result<()> {
+        self.move_to(row, column)
+    }
+
+    fn bell(&mut self) -> io::Result<()> {
+        write!(self.output, "\x07")
+    }
+
+    /// Flashes by briefly swapping the terminal's colours around.
+    fn flash(&mut self) -> io::Result<()> {
+        write!(self.output, "\x1b[?5h")?;
+        self.output.flush()?;
+        std::thread::sleep(FLASH_DURATION);
+        write!(self.output, "\x1b[?5l")?;
+        self.output.flush()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+impl<Output: Write> Drop for CrosstermBackend<Output> {
+    fn drop(&mut self) {
+        self.output.flush().ok();
+        crossterm::terminal::disable_raw_mode().ok();
+        if self.reports_repeats {
+            self.output
+                .queue(crossterm::event::PopKeyboardEnhancementFlags)
+                .ok();
+        }
+        self.output
+            .queue(crossterm::event::DisableMouseCapture)
+            .ok();
+        self.output.queue(crossterm::cursor::Show).ok();
+        writeln!(self.output).ok();
+        self.output.flush().ok();
+    }
+}
+
+impl StringBackend {
+    /// An empty screen `columns` across, with no rows until the game asks for them.
+    pub fn new(columns: u16) -> Self {
+        StringBackend {
+            lines: Vec::new(),
+            columns,
+            cursor: None,
+            drew_off_screen: false,
+        }
+    }
+
+    /// Where the cursor was last parked, as a row and column, if it has been.
+    pub fn cursor(&self) -> Option<(u16, u16)> {
+        self.cursor
+    }
+
+    /// Whether anything has been drawn, even in part, below the last row or past the right edge,
+    /// where a real screen wouldn't have shown it.
+    pub fn drew_off_screen(&self) -> bool {
+        self.drew_off_screen
+    }
+
+    /// What's on the screen, a line for each row with the spaces at the end of each left off.
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Backend for StringBackend {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        Ok((self.columns, self.lines.len() as u16))
+    }
+
+    fn reserve_rows(&mut self, rows: u16) -> io::Result<()> {
+        let columns = self.columns.into();
+        self.lines
+            .resize(self.lines.len().max(rows.into()), vec![' '; columns]);
+        Ok(())
+    }
+
+    fn clear_screen(&mut self) -> io::Result<()> {
+        for line in &mut self.lines {
+            line.fill(' ');
+        }
+        Ok(())
+    }
+
+    fn clear_line(&mut self, row: u16, column: u16) -> io::Result<()> {
+        if let Some(line) = self.lines.get_mut(usize::from(row)) {
+            line.iter_mut().skip(column.into()).for_each(|c| *c = ' ');
+        }
+        Ok(())
+    }
+
+    fn draw_text(&mut self, row: u16, column: u16, text: &str) -> io::Result<()> {
+        let end = usize::from(column) + text.chars().count();
+        self.drew_off_screen |= usize::from(row) >= self.lines.len() || end > self.columns.into();
+        if let Some(line) = self.lines.get_mut(usize::from(row)) {
+            // Anything past the right edge is cut off, as a terminal with wrapping turned off would
+            for (slot, c) in line.iter_mut().skip(column.into()).zip(text.chars()) {
+                *slot = c;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, row: u16, column: u16, text: &str, _: TileStyle) -> io::Result<()> {
+        self.draw_text(row, column, text)
+    }
+
+    fn park_cursor(&mut self, row: u16, column: u16) -> io::Result<()> {
+        self.cursor = Some((row, column));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
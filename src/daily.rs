@@ -0,0 +1,78 @@
+//! The daily challenge, where everyone playing on the same day gets the same tiles. The seed comes
+//! from the date in UTC, so it changes at the same moment everywhere.
+//!
+//! Daily scores count towards the usual high score. They could be kept apart under a key of their
+//! own, such as the date, if comparing days ever matters.
+
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub(crate) const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A day of the calendar, in UTC.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: u32,
+    /// From 1 for January to 12 for December
+    pub month: u32,
+    /// From 1
+    pub day: u32,
+}
+
+impl Date {
+    /// The date today, in UTC. A clock set before 1970 counts as the first of January that year.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Date::from_days_since_epoch(seconds / SECONDS_PER_DAY)
+    }
+
+    /// The date `days` days after the first of January 1970.
+    pub fn from_days_since_epoch(days: u64) -> Self {
+        // Counting from the first of March 0000 puts the leap day at the end of each year, and
+        // 400-year eras repeat exactly. See https://howardhinnant.github.io/date_algorithms.html
+        let days = days + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        // Months are counted from March, with January and February at the end
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        Date {
+            year: year as u32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// The seed for the day's game. The date is written as the number `YYYYMMDD` and then
+    /// scrambled, so that neighbouring days don't get similar seeds.
+    pub fn seed(self) -> u64 {
+        let number =
+            u64::from(self.year) * 10_000 + u64::from(self.month) * 100 + u64::from(self.day);
+        // The finaliser of SplitMix64, which unlike the standard library's hashers is guaranteed
+        // to give the same answer on every platform and Rust version
+        let mut seed = number.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        seed ^ (seed >> 31)
+    }
+}
+
+/// The date as `YYYY-MM-DD`.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
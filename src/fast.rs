@@ -0,0 +1,239 @@
+//! A compact 4x4 board for searching through lots of positions quickly. Each square is a nibble of
+//! a `u64` holding the tile's exponent, with 0 for an empty square, and moves are a handful of
+//! lookups into tables worked out once for every possible row.
+//!
+//! A nibble only goes up to 15, which is 32768. Two 32768s merge on a board but couldn't in a
+//! nibble, so the biggest tile a bitboard takes is 16384.
+
+use crate::{Board, Move, Square};
+use std::{fmt, sync::OnceLock};
+
+/// The biggest exponent a nibble can hold
+const MAX_NIBBLE: u8 = 15;
+
+/// A 4x4 board packed into 64 bits. Row `y` is in bits `16 * y` up, and within it column `x` is in
+/// the nibble at bit `4 * x`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(u64);
+
+/// The board had a tile too big to fit in a nibble.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TileTooBig(pub Square);
+
+/// What sliding a row of four squares produces, for every possible row.
+struct MoveTables {
+    left: Vec<u16>,
+    right: Vec<u16>,
+    /// How much a move in either direction adds to the score, as merges are the same both ways
+    score: Vec<u32>,
+}
+
+impl fmt::Display for TileTooBig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is too big for a bitboard, which holds tiles up to {}",
+            self.0.value(),
+            1 << (MAX_NIBBLE - 1)
+        )
+    }
+}
+
+impl std::error::Error for TileTooBig {}
+
+impl MoveTables {
+    /// The tables, built the first time they're needed.
+    fn get() -> &'static MoveTables {
+        static TABLES: OnceLock<MoveTables> = OnceLock::new();
+        TABLES.get_or_init(MoveTables::build)
+    }
+
+    fn build() -> Self {
+        let mut tables = MoveTables {
+            left: vec![0; 1 << 16],
+            right: vec![0; 1 << 16],
+            score: vec![0; 1 << 16],
+        };
+        for row in 0..=u16::MAX {
+            let (left, score) = slide_row_left(row);
+            tables.left[row as usize] = left;
+            tables.right[reverse_row(row) as usize] = reverse_row(left);
+            tables.score[row as usize] = score;
+        }
+        tables
+    }
+}
+
+/// Slide a row to the left, merging equal neighbours, returning the new row and the score gained.
+fn slide_row_left(row: u16) -> (u16, u32) {
+    let mut output = [0; 4];
+    let mut next_slot = 0;
+    let mut score = 0;
+    // A tile that has arrived but could still have the next one merged into it
+    let mut waiting = None;
+    for x in 0..4 {
+        let nibble = (row >> (4 * x) & 0xf) as u8;
+        if nibble == 0 {
+            continue;
+        }
+        if waiting == Some(nibble) && nibble < MAX_NIBBLE {
+            output[next_slot - 1] = nibble + 1;
+            score += 1 << (nibble + 1);
+            waiting = None;
+        } else {
+            output[next_slot] = nibble;
+            next_slot += 1;
+            waiting = Some(nibble);
+        }
+    }
+
+    let packed = output.iter().enumerate().fold(0, |packed, (x, &nibble)| {
+        packed | u16::from(nibble) << (4 * x)
+    });
+    (packed, score)
+}
+
+/// Flip a row left-to-right.
+fn reverse_row(row: u16) -> u16 {
+    (row >> 12) | (row >> 4 & 0x00f0) | (row << 4 & 0x0f00) | (row << 12)
+}
+
+impl Bitboard {
+    /// The nibble for the square at column `x` of row `y`.
+    fn nibble(self, x: usize, y: usize) -> u8 {
+        (self.0 >> (16 * y + 4 * x) & 0xf) as u8
+    }
+
+    /// Swap the rows and columns of the board.
+    fn transpose(self) -> Self {
+        let x = self.0;
+        let a1 = x & 0xf0f0_0f0f_f0f0_0f0f;
+        let a2 = x & 0x0000_f0f0_0000_f0f0;
+        let a3 = x & 0x0f0f_0000_0f0f_0000;
+        let a = a1 | (a2 << 12) | (a3 >> 12);
+        let b1 = a & 0xff00_ff00_00ff_00ff;
+        let b2 = a & 0x00ff_00ff_0000_0000;
+        let b3 = a & 0x0000_0000_ff00_ff00;
+        Bitboard(b1 | (b2 >> 24) | (b3 << 24))
+    }
+
+    /// Slide every row using the given table, adding up the score as it goes.
+    fn slide_rows(self, table: &[u16]) -> (Self, u32) {
+        let tables = MoveTables::get();
+        let mut output = 0;
+        let mut score = 0;
+        for y in 0..4 {
+            let row = (self.0 >> (16 * y)) as u16;
+            output |= u64::from(table[row as usize]) << (16 * y);
+            score += tables.score[row as usize];
+        }
+        (Bitboard(output), score)
+    }
+
+    /// Apply a move, returning the new board along with how much the move adds to the score.
+    pub fn apply_move_with_score(self, direction: Move) -> (Self, u32) {
+        let tables = MoveTables::get();
+        match direction {
+            Move::Left => self.slide_rows(&tables.left),
+            Move::Right => self.slide_rows(&tables.right),
+            Move::Up => {
+                let (board, score) = self.transpose().slide_rows(&tables.left);
+                (board.transpose(), score)
+            }
+            Move::Down => {
+                let (board, score) = self.transpose().slide_rows(&tables.right);
+                (board.transpose(), score)
+            }
+        }
+    }
+
+    pub fn apply_move(self, direction: Move) -> Self {
+        self.apply_move_with_score(direction).0
+    }
+
+    /// How many cells on the board are empty.
+    pub fn count_empty(self) -> usize {
+        (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.nibble(x, y) == 0)
+            .count()
+    }
+}
+
+impl Board {
+    /// The same board as [Board::apply_move] gives, worked out a lane at a time with the
+    /// bitboard's lookup tables instead of by sliding the tiles. Boards with tiles too big for the
+    /// tables take the usual route.
+    pub fn apply_move_fast(self, direction: Move) -> Self {
+        let tables = MoveTables::get();
+        let table = match direction {
+            Move::Left | Move::Up => &tables.left,
+            Move::Right | Move::Down => &tables.right,
+        };
+        // The row and column of the square `index` along `lane`, going left to right or top to
+        // bottom
+        let position = |lane: usize, index: usize| match direction {
+            Move::Left | Move::Right => (lane, index),
+            Move::Up | Move::Down => (index, lane),
+        };
+
+        let mut output = self;
+        for lane in 0..4 {
+            let mut packed = 0;
+            for index in 0..4 {
+                let (y, x) = position(lane, index);
+                if let Some(cell) = self.rows[y][x] {
+                    // Two 32768s merge on a board but not in the tables, so they're too big too
+                    if cell.0 + 1 >= MAX_NIBBLE {
+                        return self.apply_move(direction);
+                    }
+                    packed |= u16::from(cell.0 + 1) << (4 * index);
+                }
+            }
+
+            let slid = table[packed as usize];
+            output.score += tables.score[packed as usize];
+            for index in 0..4 {
+                let (y, x) = position(lane, index);
+                let nibble = (slid >> (4 * index) & 0xf) as u8;
+                output.rows[y][x] = (nibble != 0).then(|| Square(nibble - 1));
+            }
+        }
+        output
+    }
+}
+
+impl TryFrom<Board> for Bitboard {
+    type Error = TileTooBig;
+
+    fn try_from(board: Board) -> Result<Self, Self::Error> {
+        let mut packed = 0;
+        for (y, row) in board.rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(cell) = *cell {
+                    // As in apply_move_fast, a 32768 could be merged into a tile the tables
+                    // don't have
+                    if cell.0 + 1 >= MAX_NIBBLE {
+                        return Err(TileTooBig(cell));
+                    }
+                    packed |= u64::from(cell.0 + 1) << (16 * y + 4 * x);
+                }
+            }
+        }
+        Ok(Bitboard(packed))
+    }
+}
+
+/// The board with the same tiles. Bitboards don't keep a score, so it starts from zero.
+impl From<Bitboard> for Board {
+    fn from(bitboard: Bitboard) -> Self {
+        let mut board = Board::new();
+        for (y, row) in board.rows.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let nibble = bitboard.nibble(x, y);
+                *cell = (nibble != 0).then(|| Square(nibble - 1));
+            }
+        }
+        board
+    }
+}
@@ -0,0 +1,41 @@
+//! Big characters for the numbers on tiles, drawn like a seven-segment display so that they can be
+//! read from across the room.
+
+/// How many rows each big character takes up
+pub(crate) const GLYPH_HEIGHT: u16 = 3;
+/// How many columns each big character takes up. The segments leave a gap down the edges of most
+/// characters, so they can go right next to each other.
+pub(crate) const GLYPH_WIDTH: u16 = 3;
+
+/// The rows of the big version of a character, or blanks for one that never shows up on a tile.
+fn glyph(c: char) -> [&'static str; GLYPH_HEIGHT as usize] {
+    match c {
+        '0' => [" _ ", "| |", "|_|"],
+        '1' => ["   ", "  |", "  |"],
+        '2' => [" _ ", " _|", "|_ "],
+        '3' => [" _ ", " _|", " _|"],
+        '4' => ["   ", "|_|", "  |"],
+        '5' => [" _ ", "|_ ", " _|"],
+        '6' => [" _ ", "|_ ", "|_|"],
+        '7' => [" _ ", "  |", "  |"],
+        '8' => [" _ ", "|_|", "|_|"],
+        '9' => [" _ ", "|_|", " _|"],
+        'k' => ["|  ", "|/ ", "|\\ "],
+        'M' => ["   ", "|V|", "| |"],
+        'G' => [" _ ", "|  ", "|_]"],
+        '^' => ["/\\ ", "   ", "   "],
+        _ => ["   ", "   ", "   "],
+    }
+}
+
+/// The rows of `text` written in big characters, each [GLYPH_WIDTH] columns across for every
+/// character of the text.
+pub(crate) fn big_text(text: &str) -> [String; GLYPH_HEIGHT as usize] {
+    let mut lines: [String; GLYPH_HEIGHT as usize] = Default::default();
+    for c in text.chars() {
+        for (line, part) in lines.iter_mut().zip(glyph(c)) {
+            line.push_str(part);
+        }
+    }
+    lines
+}
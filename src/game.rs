@@ -0,0 +1,535 @@
+use crate::{
+    check_spawns,
+    scoring::{Additive, ScoringRule},
+    Board, Clock, Move, MoveOutcome, Square, DEFAULT_SPAWNS,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    fs, io,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// How many moves can be undone, unless changed with [Game::set_undo_depth]
+pub const DEFAULT_UNDO_DEPTH: usize = 16;
+
+/// How many tiles a new game starts with, unless it's made with [Game::with_starting_tiles]
+pub const DEFAULT_STARTING_TILES: usize = 2;
+
+/// The first line of a saved game, which changes whenever the format does so that older saves are
+/// turned away rather than misread
+const SAVE_HEADER: &str = "2048 save 1";
+
+/// A game of 2048 in progress: the board, along with everything needed to keep playing it.
+///
+/// Everything random in the game, which tiles are added and where, comes from the game's own
+/// random number generator. A seeded or mock generator makes every board of the game predictable.
+pub struct Game<R: Rng, const W: usize = 4, const H: usize = W> {
+    board: Board<W, H>,
+    rng: R,
+    moves_made: u32,
+    /// How many merges the moves so far have made between them
+    merges_made: u32,
+    /// The boards from before each of the most recent moves, newest last, along with how many
+    /// merges had been made by then
+    undo_stack: VecDeque<(Board<W, H>, u32)>,
+    undo_depth: usize,
+    /// The boards that undone moves led to, most recently undone last, along with how many merges
+    /// had been made by then and the move itself if it was in the history. Emptied by any new move.
+    redo_stack: Vec<(Board<W, H>, u32, Option<Move>)>,
+    /// Every move that changed the board, oldest first, since the game started or the history was
+    /// last cleared
+    history: Vec<Move>,
+    /// The tiles that can be added after each move, with how likely each one is
+    spawns: Vec<(Square, f64)>,
+    /// Whether a tile is added after each move, which is only turned off for studying moves
+    auto_spawn: bool,
+    /// The tile that wins the game
+    target: Square,
+    /// How much each move adds to the score
+    scoring: Box<dyn ScoringRule>,
+    /// What the last merge of the most recent move was multiplied by
+    last_multiplier: u32,
+    observers: Vec<Box<dyn GameObserver<W, H>>>,
+    /// How long the game has been played for. It's up to the front end to start and stop it.
+    clock: Clock,
+    /// How long the game can be played for, if it's against the clock
+    time_limit: Option<Duration>,
+}
+
+/// Something that wants to hear about what happens in a game as it's played, such as a log or a
+/// front end. Every method does nothing unless it's overridden.
+pub trait GameObserver<const W: usize = 4, const H: usize = W> {
+    /// A move changed the board. The outcome is from before the new tile was added.
+    fn on_move(&mut self, _direction: Move, _outcome: &MoveOutcome<W, H>) {}
+
+    /// A new tile was added at column `x` of row `y` after a move.
+    fn on_spawn(&mut self, _x: usize, _y: usize, _square: Square) {}
+
+    /// A move made the [winning tile](Game::target) for the first time.
+    fn on_win(&mut self) {}
+
+    /// There are no moves left, with the game ending on the given score.
+    fn on_game_over(&mut self, _score: u32) {}
+}
+
+/// What happened when a move was played.
+#[derive(Copy, Clone, Debug)]
+pub struct StepResult {
+    /// Whether the move changed the board. Moves that don't are ignored entirely.
+    pub moved: bool,
+    /// Whether the game is over after this move
+    pub game_over: bool,
+    /// How many merges the move made
+    pub merged_tiles: u8,
+    /// Where the tile added after the move went, as `(x, y)`, if one was added
+    pub new_tile: Option<(usize, usize)>,
+}
+
+/// Everything that happened in a turn of [Game::play].
+#[derive(Copy, Clone, Debug)]
+pub struct TurnReport<const W: usize = 4, const H: usize = W> {
+    /// The move itself, with the board it left before the new tile was added
+    pub outcome: MoveOutcome<W, H>,
+    /// Where the tile added after the move went, as `(x, y)`, and which tile it was, if one was
+    /// added
+    pub spawned: Option<((usize, usize), Square)>,
+    /// Whether the game is over after the turn
+    pub game_over: bool,
+}
+
+impl<R: Rng, const W: usize, const H: usize> Game<R, W, H> {
+    /// Start a new game, with two tiles placed on the board.
+    pub fn new(rng: R) -> Self {
+        Game::with_starting_tiles(rng, DEFAULT_STARTING_TILES)
+    }
+
+    /// Start a new game that gets everything random, from where the first tiles go to which tile
+    /// comes after each move, from `rng`. A seeded or mock generator makes the whole game
+    /// repeatable.
+    pub fn with_rng(rng: R) -> Self {
+        Game::new(rng)
+    }
+
+    /// Start a new game with the given number of tiles placed on the board, for variants that
+    /// start with more or fewer than two. Panics if there are more tiles than squares.
+    pub fn with_starting_tiles(mut rng: R, tiles: usize) -> Self {
+        assert!(
+            tiles <= W * H,
+            "A board with {} squares can't start with {} tiles",
+            W * H,
+            tiles
+        );
+        let mut board = Board::new();
+        for _ in 0..tiles {
+            board.add_square(&mut rng);
+        }
+        Game::from_board(rng, board)
+    }
+
+    /// Start a game from a board of your choosing, such as a puzzle's opening, rather than two
+    /// random tiles. Tiles are added after each move as usual.
+    pub fn from_board(rng: R, board: Board<W, H>) -> Self {
+        Game {
+            board,
+            rng,
+            moves_made: 0,
+            merges_made: 0,
+            undo_stack: VecDeque::new(),
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            redo_stack: Vec::new(),
+            history: Vec::new(),
+            spawns: DEFAULT_SPAWNS.to_vec(),
+            auto_spawn: true,
+            target: Square::WIN,
+            scoring: Box::new(Additive),
+            last_multiplier: 1,
+            observers: Vec::new(),
+            clock: Clock::default(),
+            time_limit: None,
+        }
+    }
+
+    /// Carry on the game with a different random number generator, such as a mock for a test or a
+    /// freshly seeded one. Nothing else about the game changes.
+    pub fn replace_rng<R2: Rng>(self, rng: R2) -> Game<R2, W, H> {
+        Game {
+            board: self.board,
+            rng,
+            moves_made: self.moves_made,
+            merges_made: self.merges_made,
+            undo_stack: self.undo_stack,
+            undo_depth: self.undo_depth,
+            redo_stack: self.redo_stack,
+            history: self.history,
+            spawns: self.spawns,
+            auto_spawn: self.auto_spawn,
+            target: self.target,
+            scoring: self.scoring,
+            last_multiplier: self.last_multiplier,
+            observers: self.observers,
+            clock: self.clock,
+            time_limit: self.time_limit,
+        }
+    }
+
+    /// Tell an observer about everything that happens from now on.
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver<W, H>>) {
+        self.observers.push(observer);
+    }
+
+    /// Change how many moves can be undone, forgetting the oldest ones if there are too many.
+    pub fn set_undo_depth(&mut self, depth: usize) {
+        self.undo_depth = depth;
+        while self.undo_stack.len() > depth {
+            self.undo_stack.pop_front();
+        }
+    }
+
+    /// Change how often the tiles added after each move are a 4 rather than a 2. The probability
+    /// must be between 0 and 1.
+    pub fn set_four_probability(&mut self, four_probability: f64) {
+        assert!(
+            (0.0..=1.0).contains(&four_probability),
+            "The chance of a 4 must be between 0 and 1, not {}",
+            four_probability
+        );
+        self.spawns = vec![
+            (Square(0), 1.0 - four_probability),
+            (Square(1), four_probability),
+        ];
+    }
+
+    /// Change which tiles are added after each move, for variants of the game. Each tile is picked
+    /// with a chance in proportion to its weight, so `[(2, 0.9), (4, 0.1)]` is the usual game. The
+    /// weights must not be negative, and must not all be zero.
+    pub fn set_spawns(&mut self, spawns: &[(Square, f64)]) {
+        check_spawns(spawns);
+        self.spawns = spawns.to_vec();
+    }
+
+    /// Which tiles are added after each move, along with their weights.
+    pub fn spawns(&self) -> &[(Square, f64)] {
+        &self.spawns
+    }
+
+    /// Choose whether a new tile is added after each move. With it turned off the board only
+    /// changes by moving, which is for studying how moves work rather than for playing.
+    pub fn set_auto_spawn(&mut self, auto_spawn: bool) {
+        self.auto_spawn = auto_spawn;
+    }
+
+    /// Whether a new tile is added after each move, as in normal play.
+    pub fn auto_spawn(&self) -> bool {
+        self.auto_spawn
+    }
+
+    /// Change the tile that wins the game, for an easier or harder game than getting to 2048.
+    pub fn set_target(&mut self, target: Square) {
+        self.target = target;
+    }
+
+    /// The tile that wins the game, which is [Square::WIN] unless it's been changed.
+    pub fn target(&self) -> Square {
+        self.target
+    }
+
+    /// Change how much each move adds to the score from now on, for variants of the game.
+    pub fn set_scoring_rule(&mut self, scoring: Box<dyn ScoringRule>) {
+        self.scoring = scoring;
+    }
+
+    /// What the last merge of the most recent move that changed the board was multiplied by when
+    /// it was scored, which is 1 unless the scoring rule says otherwise.
+    pub fn last_multiplier(&self) -> u32 {
+        self.last_multiplier
+    }
+
+    /// Play a move. If it changes the board a new tile is added afterwards, unless that's been
+    /// turned off, otherwise the game is left as it was.
+    pub fn step(&mut self, direction: Move) -> StepResult {
+        match self.play(direction) {
+            Some(report) => StepResult {
+                moved: true,
+                game_over: report.game_over,
+                merged_tiles: report.outcome.merged_tiles,
+                new_tile: report.spawned.map(|(position, _)| position),
+            },
+            None => StepResult {
+                moved: false,
+                game_over: self.board.is_game_over(),
+                merged_tiles: 0,
+                new_tile: None,
+            },
+        }
+    }
+
+    /// Play a whole turn: make the move, then add a new tile unless that's been turned off,
+    /// keeping the score and move count up to date. Returns what happened, or `None` without
+    /// changing anything if the move doesn't change the board.
+    pub fn play(&mut self, direction: Move) -> Option<TurnReport<W, H>> {
+        let mut outcome = self.board.apply_move_detailed(direction);
+        if !outcome.moved {
+            return None;
+        }
+
+        let merged = outcome.merged_squares(direction);
+        outcome.score_gained = self.scoring.score(&merged);
+        outcome.board.score = self.board.score() + outcome.score_gained;
+        self.last_multiplier = self.scoring.multiplier(merged.len());
+
+        self.push_undo();
+        self.redo_stack.clear();
+        self.history.push(direction);
+        let had_won = self.board.has_won(self.target);
+        self.board = outcome.board;
+        let spawned = if self.auto_spawn {
+            self.board
+                .add_square_from(&mut self.rng, &self.spawns)
+                .and_then(|(x, y)| Some(((x, y), self.board.get(x, y)?)))
+        } else {
+            None
+        };
+        self.moves_made += 1;
+        self.merges_made += u32::from(outcome.merged_tiles);
+
+        for observer in &mut self.observers {
+            observer.on_move(direction, &outcome);
+        }
+        if let Some(((x, y), square)) = spawned {
+            for observer in &mut self.observers {
+                observer.on_spawn(x, y, square);
+            }
+        }
+        if !had_won && self.board.has_won(self.target) {
+            for observer in &mut self.observers {
+                observer.on_win();
+            }
+        }
+
+        let game_over = self.board.is_game_over();
+        if game_over {
+            let score = self.board.score();
+            for observer in &mut self.observers {
+                observer.on_game_over(score);
+            }
+        }
+
+        Some(TurnReport {
+            outcome,
+            spawned,
+            game_over,
+        })
+    }
+
+    /// Remember the game as it is, so that the move about to be made can be undone.
+    fn push_undo(&mut self) {
+        if self.undo_depth > 0 {
+            if self.undo_stack.len() == self.undo_depth {
+                self.undo_stack.pop_front();
+            }
+            self.undo_stack.push_back((self.board, self.merges_made));
+        }
+    }
+
+    /// Take back the most recent move, restoring the board and score from before it. Returns
+    /// whether there was anything to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop_back() {
+            Some((board, merges_made)) => {
+                let direction = self.history.pop();
+                self.redo_stack
+                    .push((self.board, self.merges_made, direction));
+                self.board = board;
+                self.moves_made -= 1;
+                self.merges_made = merges_made;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Make the most recently undone move again, as long as no other move has been made since.
+    /// The board comes back exactly as it was, including the tile added after the move. Returns
+    /// whether there was anything to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some((board, merges_made, direction)) => {
+                self.push_undo();
+                self.history.extend(direction);
+                self.board = board;
+                self.moves_made += 1;
+                self.merges_made = merges_made;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The current board.
+    pub fn board(&self) -> &Board<W, H> {
+        &self.board
+    }
+
+    /// The score so far, which is carried on the board.
+    pub fn score(&self) -> u32 {
+        self.board.score()
+    }
+
+    /// How many moves have changed the board so far.
+    pub fn moves_made(&self) -> u32 {
+        self.moves_made
+    }
+
+    /// How many merges the moves so far have made between them.
+    pub fn merges_made(&self) -> u32 {
+        self.merges_made
+    }
+
+    /// Every move that has changed the board, oldest first, leaving out any that were undone. Only
+    /// moves since the history was last cleared are kept.
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Forget the moves made so far, such as to save memory in a very long game. Moves from before
+    /// can still be undone.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// The clock timing the game, which starts off stopped.
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// The clock timing the game, for starting and stopping it.
+    pub fn clock_mut(&mut self) -> &mut Clock {
+        &mut self.clock
+    }
+
+    /// Give the game a time limit, for a blitz to score as much as possible before it runs out, or
+    /// take it away with `None`. The time is counted by the game's [clock](Game::clock).
+    pub fn set_time_limit(&mut self, time_limit: Option<Duration>) {
+        self.time_limit = time_limit;
+    }
+
+    /// How long the game can be played for, if it's against the clock.
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Whether the time limit has run out as of `now`. Nothing is stopped when it does: a move
+    /// already under way is finished, and it's up to the front end to end the game after it.
+    pub fn out_of_time(&self, now: Instant) -> bool {
+        self.time_limit
+            .is_some_and(|limit| self.clock.elapsed(now) >= limit)
+    }
+}
+
+/// Saving a game to come back to later. Only games whose random number generator can be saved
+/// along with them can be saved, so that the tiles after loading are the ones that would have
+/// come anyway.
+///
+/// A saved game is plain text: a header line, then the generator's state, score, moves and
+/// merges as `name value` lines, then the board as its `Display` impl writes it, which keeps every
+/// cell apart however big its tile is. Settings such as the target and scoring rule aren't saved,
+/// as they come from whatever is loading the game, and nothing can be undone after loading.
+///
+/// The format is written by hand, like recordings, rather than through the `serde` feature: serde
+/// only describes the data, and the crate has no data format such as JSON to write it with.
+impl<const W: usize, const H: usize> Game<ChaCha12Rng, W, H> {
+    /// Write the game to a file.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let seed = self
+            .rng
+            .get_seed()
+            .iter()
+            .fold(String::new(), |mut seed, byte| {
+                write!(seed, "{:02x}", byte).unwrap();
+                seed
+            });
+        let contents = format!(
+            "{}\nseed {}\nstream {}\nposition {}\nscore {}\nmoves {}\nmerges {}\n{}\n",
+            SAVE_HEADER,
+            seed,
+            self.rng.get_stream(),
+            self.rng.get_word_pos(),
+            self.board.score(),
+            self.moves_made,
+            self.merges_made,
+            self.board,
+        );
+        fs::write(path, contents)
+    }
+
+    /// Read a game written by [Game::save]. Files that aren't a saved game, or were saved in a
+    /// different format, are rejected with [io::ErrorKind::InvalidData].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut lines = contents.lines();
+        match lines.next() {
+            Some(header) if header.trim() == SAVE_HEADER => {}
+            _ => {
+                return Err(invalid(
+                    "not a saved game, or one from another version".into(),
+                ))
+            }
+        }
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.trim().strip_prefix(name)?.strip_prefix(' '))
+                .map(str::trim)
+                .ok_or_else(|| invalid(format!("the save is missing its {}", name)))
+        };
+        let seed_text = field("seed")?;
+        let stream = field("stream")?;
+        let position = field("position")?;
+        let score = field("score")?;
+        let moves = field("moves")?;
+        let merges = field("merges")?;
+
+        let mut seed = [0; 32];
+        let digits = seed_text.as_bytes().chunks(2);
+        if seed_text.len() != seed.len() * 2 {
+            return Err(invalid(format!("{:?} is not a seed", seed_text)));
+        }
+        for (byte, digits) in seed.iter_mut().zip(digits) {
+            *byte = std::str::from_utf8(digits)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| invalid(format!("{:?} is not a seed", seed_text)))?;
+        }
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        rng.set_stream(parse_field("stream", stream)?);
+        rng.set_word_pos(parse_field("position", position)?);
+
+        let rest = lines.collect::<Vec<_>>().join("\n");
+        let mut board = rest
+            .parse::<Board<W, H>>()
+            .map_err(|error| invalid(format!("the board is invalid: {}", error)))?;
+        board.score = parse_field("score", score)?;
+
+        let mut game = Game::from_board(rng, board);
+        game.moves_made = parse_field("moves", moves)?;
+        game.merges_made = parse_field("merges", merges)?;
+        Ok(game)
+    }
+}
+
+/// Read the value of a field of a saved game.
+fn parse_field<T: FromStr>(name: &str, text: &str) -> io::Result<T> {
+    text.parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} is not a valid {}", text, name),
+        )
+    })
+}
@@ -0,0 +1,50 @@
+//! Remembering the best game across sessions, in a small text file in the user's home directory.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The best results seen so far.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighScore {
+    pub score: u32,
+    /// The number on the biggest tile reached
+    pub max_tile: u32,
+}
+
+impl HighScore {
+    /// Where the high score lives by default, `~/.2048_highscore`, if there's a home directory.
+    pub fn default_path() -> Option<PathBuf> {
+        let home = std::env::var_os("HOME")?;
+        Some(Path::new(&home).join(".2048_highscore"))
+    }
+
+    /// Read the high score from a file. A missing or unreadable file is treated as no high score
+    /// at all.
+    pub fn load(path: &Path) -> Self {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return HighScore::default(),
+        };
+
+        let mut lines = contents.lines().map(|line| line.trim().parse::<u32>());
+        match (lines.next(), lines.next()) {
+            (Some(Ok(score)), Some(Ok(max_tile))) => HighScore { score, max_tile },
+            _ => HighScore::default(),
+        }
+    }
+
+    /// Write the high score to a file, as the score and then the max tile on their own lines.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, format!("{}\n{}\n", self.score, self.max_tile))
+    }
+
+    /// Take a finished game into account. Returns whether anything improved.
+    pub fn update(&mut self, score: u32, max_tile: u32) -> bool {
+        let improved = score > self.score || max_tile > self.max_tile;
+        self.score = self.score.max(score);
+        self.max_tile = self.max_tile.max(max_tile);
+        improved
+    }
+}
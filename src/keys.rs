@@ -0,0 +1,149 @@
+//! Which keys play which moves.
+//!
+//! Bindings can be read from a plain text file with one binding on each line: the key, then the
+//! move, as in `k up`. A key is either a single character or one of `up`, `down`, `left` and
+//! `right` for the arrow keys, and a move is one of `up`, `down`, `left` and `right`.
+
+use crate::Move;
+use crossterm::event::KeyCode;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// A mapping from keys to the moves they play. Keys that aren't mapped don't play anything.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    moves: HashMap<KeyCode, Move>,
+}
+
+/// Slows down moves made by holding a key down, however fast the terminal repeats it. A key that's
+/// pressed again always counts. All times are passed in rather than read from the system.
+#[derive(Copy, Clone, Debug)]
+pub struct KeyRepeat {
+    interval: Duration,
+    /// When a key last played a move, whether it was pressed or held down
+    last_key_at: Option<Instant>,
+}
+
+impl KeyRepeat {
+    /// Let a held key play a move at most once every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        KeyRepeat {
+            interval,
+            last_key_at: None,
+        }
+    }
+
+    /// Note that a key was pressed at `now`, which plays its move whenever it comes.
+    pub fn press(&mut self, now: Instant) {
+        self.last_key_at = Some(now);
+    }
+
+    /// Whether a key held down at `now` plays its move again, which it does once the interval has
+    /// passed since the last key that did.
+    pub fn repeat(&mut self, now: Instant) -> bool {
+        if self
+            .last_key_at
+            .is_some_and(|last| now < last + self.interval)
+        {
+            return false;
+        }
+        self.last_key_at = Some(now);
+        true
+    }
+}
+
+impl KeyBindings {
+    /// Bindings with no keys mapped at all.
+    pub fn new() -> Self {
+        KeyBindings {
+            moves: HashMap::new(),
+        }
+    }
+
+    /// Make a key play the given move, replacing whatever it played before.
+    pub fn bind(&mut self, key: KeyCode, direction: Move) {
+        self.moves.insert(key, direction);
+    }
+
+    /// Stop a key from playing anything.
+    pub fn unbind(&mut self, key: KeyCode) {
+        self.moves.remove(&key);
+    }
+
+    /// The move a key plays, if any.
+    pub fn get(&self, key: KeyCode) -> Option<Move> {
+        self.moves.get(&key).copied()
+    }
+
+    /// Read bindings from a file. Only the keys in the file are bound.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut bindings = KeyBindings::new();
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let (key, direction) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| invalid(format!("{:?} needs a key and a move", line)))?;
+            let key = parse_key(key).ok_or_else(|| invalid(format!("{:?} is not a key", key)))?;
+            let direction = parse_move(direction.trim())
+                .ok_or_else(|| invalid(format!("{:?} is not a move", direction.trim())))?;
+            bindings.bind(key, direction);
+        }
+        Ok(bindings)
+    }
+}
+
+/// The arrow keys, WASD and vim's hjkl.
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut bindings = KeyBindings::new();
+        for (arrow, wasd, vim, direction) in [
+            (KeyCode::Up, 'w', 'k', Move::Up),
+            (KeyCode::Down, 's', 'j', Move::Down),
+            (KeyCode::Left, 'a', 'h', Move::Left),
+            (KeyCode::Right, 'd', 'l', Move::Right),
+        ] {
+            bindings.bind(arrow, direction);
+            bindings.bind(KeyCode::Char(wasd), direction);
+            bindings.bind(KeyCode::Char(vim), direction);
+        }
+        bindings
+    }
+}
+
+/// A key as it's written in a bindings file.
+fn parse_key(text: &str) -> Option<KeyCode> {
+    if let Some(direction) = parse_move(text) {
+        return Some(match direction {
+            Move::Up => KeyCode::Up,
+            Move::Down => KeyCode::Down,
+            Move::Left => KeyCode::Left,
+            Move::Right => KeyCode::Right,
+        });
+    }
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(KeyCode::Char(c)),
+        _ => None,
+    }
+}
+
+/// A move as it's written in a bindings file.
+fn parse_move(text: &str) -> Option<Move> {
+    match text {
+        "up" => Some(Move::Up),
+        "down" => Some(Move::Down),
+        "left" => Some(Move::Left),
+        "right" => Some(Move::Right),
+        _ => None,
+    }
+}
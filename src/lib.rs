@@ -1,27 +1,83 @@
-use crossterm::QueueableCommand;
+use merge::{Doubling, MergeRule};
 use rand::Rng;
 use std::{
-    cmp::Ordering,
-    io::{ErrorKind, Write},
+    fmt,
+    hash::{Hash, Hasher},
     iter::zip,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    str::FromStr,
+    time::{Duration, Instant},
 };
 
-const SIZE_USIZE: usize = 4;
-const SIZE: u16 = 4;
+#[cfg(feature = "tui")]
+mod backend;
+pub mod daily;
+pub mod fast;
+mod font;
+mod game;
+pub mod highscore;
+#[cfg(feature = "tui")]
+pub mod keys;
+pub mod merge;
+pub mod recording;
+#[cfg(feature = "tui")]
+mod render;
+pub mod report;
+pub mod scoring;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod simulation;
+pub mod snapshot;
+pub mod solver;
+pub mod spectate;
+#[cfg(feature = "tui")]
+mod theme;
+
+#[cfg(feature = "tui")]
+pub use backend::{Backend, CrosstermBackend, StringBackend};
+pub use game::{
+    Game, GameObserver, StepResult, TurnReport, DEFAULT_STARTING_TILES, DEFAULT_UNDO_DEPTH,
+};
+/// The random number generator behind games that can be [saved](Game::save). It gives the same
+/// numbers as [rand::rngs::StdRng] from the same seed.
+pub use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "tui")]
+pub use render::{Animation, Renderer};
+#[cfg(feature = "tui")]
+pub use theme::{Theme, TileStyle};
+
 const MAX_DIGIT_WIDTH: u16 = 5;
+/// The number on the biggest tile that can be written as a `u32`. Bigger tiles are written as a
+/// power of two, such as `2^40`.
+const MAX_TILE_VALUE: u32 = 1 << 31;
 
-/// A number to go into a single square on the 2048 board.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// How often a new tile is a 4 rather than a 2, as in the original game
+pub const DEFAULT_FOUR_PROBABILITY: f64 = 0.1;
+
+/// The tiles added after each move and how likely each one is, as in the original game: a 2 or,
+/// with probability [DEFAULT_FOUR_PROBABILITY], a 4
+pub const DEFAULT_SPAWNS: [(Square, f64); 2] = [
+    (Square(0), 1.0 - DEFAULT_FOUR_PROBABILITY),
+    (Square(1), DEFAULT_FOUR_PROBABILITY),
+];
+
+/// A number to go into a single square on the 2048 board. Squares are ordered by their value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Square(u8);
 
-/// A whole board of 2048
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct Board {
-    rows: [[Option<Square>; SIZE_USIZE]; SIZE_USIZE],
+/// A whole board of 2048, `W` squares across and `H` down. Boards are square unless given a
+/// height.
+#[derive(Copy, Clone, Debug)]
+pub struct Board<const W: usize = 4, const H: usize = W> {
+    rows: [[Option<Square>; W]; H],
+    /// The total value of every merge made to get to this board
+    score: u32,
 }
 
 /// A user move that can be applied to a board.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Move {
     Up,
     Down,
@@ -29,74 +85,454 @@ pub enum Move {
     Right,
 }
 
+/// Why a board couldn't be read from text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBoardError {
+    /// There weren't the right number of rows
+    WrongRowCount { expected: usize, found: usize },
+    /// A row didn't have the right number of cells
+    WrongColumnCount {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A cell was neither a number nor an empty marker
+    InvalidCell(String),
+    /// A number that isn't a power of two, and so can't be on a tile
+    NotPowerOfTwo(u32),
+    /// A power of two that is too small or too big to be a tile
+    OutOfRange(u32),
+}
+
+/// Why a move couldn't be read from text, holding the text that wasn't a move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMoveError(pub String);
+
+/// Everything that happened as a result of applying a move to a board.
+#[derive(Copy, Clone, Debug)]
+pub struct MoveOutcome<const W: usize = 4, const H: usize = W> {
+    /// The board after the move
+    pub board: Board<W, H>,
+    /// Whether any tile slid or merged
+    pub moved: bool,
+    /// How many merges took place
+    pub merged_tiles: u8,
+    /// Which squares of the new board hold a tile made by a merge, by row then column
+    pub merged_cells: [[bool; W]; H],
+    /// How much the move added to the score
+    pub score_gained: u32,
+}
+
 impl Square {
+    /// The tile that wins the game unless a different target is chosen, 2048.
+    pub const WIN: Square = Square(10);
+
+    /// The tile made by merging two of this one. The biggest tile a square can hold stays as it
+    /// is rather than overflowing.
     pub fn inc(self) -> Self {
-        Square(self.0 + 1)
-    }
-
-    pub fn color(self) -> (crossterm::style::Color, bool) {
-        let (r, g, b, is_dark) = match self.0 {
-            0 => (238, 228, 218, true),   // 2
-            1 => (237, 224, 200, true),   // 4
-            2 => (242, 177, 121, false),  // 8
-            3 => (245, 149, 99, false),   // 16
-            4 => (246, 124, 95, false),   // 32
-            5 => (246, 94, 59, false),    // 64
-            6 => (237, 207, 114, true),   // 128
-            7 => (237, 204, 97, false),   // 256
-            8 => (237, 200, 80, false),   // 512
-            9 => (237, 197, 63, false),   // 1024
-            10 => (237, 194, 68, false),  // 2048
-            11 => (181, 134, 180, false), // 4096
-            12 => (168, 97, 171, false),  // 8192
-            13 => (160, 72, 163, false),  // 16 384
-            14 => (128, 0, 128, false),   // 32 768
-            15 => (96, 0, 70, false),     // 65 536
-            _ => unreachable!(),          // not possible to create these in gameplay
+        Square(self.0.saturating_add(1))
+    }
+
+    /// The number written on the tile, or `u32::MAX` for tiles too big to count that high.
+    pub fn value(self) -> u32 {
+        2u32.checked_shl(self.0.into())
+            .filter(|&value| value != 0)
+            .unwrap_or(u32::MAX)
+    }
+
+    /// The tile with the given number written on it, if it's a power of two from 2 up.
+    pub fn from_value(value: u32) -> Option<Self> {
+        if value.is_power_of_two() && (2..=MAX_TILE_VALUE).contains(&value) {
+            Some(Square(value.trailing_zeros() as u8 - 1))
+        } else {
+            None
+        }
+    }
+
+    /// The number on the tile as a board's text has it, or a power of two such as `2^40` for
+    /// tiles too big to count in a `u32`.
+    fn text(self) -> String {
+        if self.value() == u32::MAX {
+            format!("2^{}", u32::from(self.0) + 1)
+        } else {
+            self.value().to_string()
+        }
+    }
+
+    /// The number written on the tile, shortened if it won't fit in `width` characters: to
+    /// thousands as in "2k" for 2048, then millions and billions, and failing that a power of two
+    /// as in "2^40".
+    fn label(self, width: usize) -> String {
+        let exponent = u32::from(self.0) + 1;
+        let power = format!("2^{}", exponent);
+        let Some(value) = 1u64.checked_shl(exponent) else {
+            return power;
         };
-        let background = crossterm::style::Color::Rgb { r, g, b };
-        (background, is_dark)
+        let mut shortened = [("", 0), ("k", 10), ("M", 20), ("G", 30)]
+            .into_iter()
+            .filter(|&(_, shift)| exponent >= shift)
+            .map(|(suffix, shift)| format!("{}{}", value >> shift, suffix));
+        shortened
+            .find(|label| label.len() <= width)
+            .unwrap_or(power)
     }
 }
 
-impl Board {
+impl Move {
+    /// Every move there is.
+    pub fn all() -> [Move; 4] {
+        [Move::Up, Move::Down, Move::Left, Move::Right]
+    }
+
+    /// The move in the other direction.
+    pub fn opposite(self) -> Move {
+        match self {
+            Move::Up => Move::Down,
+            Move::Down => Move::Up,
+            Move::Left => Move::Right,
+            Move::Right => Move::Left,
+        }
+    }
+}
+
+/// Reads a move from its first letter, `u`, `d`, `l` or `r`, in either case. WASD letters aren't
+/// accepted, as `d` would mean both down and right.
+impl TryFrom<char> for Move {
+    type Error = ParseMoveError;
+
+    fn try_from(letter: char) -> Result<Self, Self::Error> {
+        match letter.to_ascii_lowercase() {
+            'u' => Ok(Move::Up),
+            'd' => Ok(Move::Down),
+            'l' => Ok(Move::Left),
+            'r' => Ok(Move::Right),
+            _ => Err(ParseMoveError(letter.to_string())),
+        }
+    }
+}
+
+/// Reads a move from its first letter, as [Move]'s `TryFrom<char>` impl does, or its whole name,
+/// such as `up` or `Left`. Either way, case doesn't matter and whitespace around it is ignored.
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        let mut letters = text.chars();
+        let direction = match (letters.next(), letters.next()) {
+            (Some(letter), None) => Move::try_from(letter).ok(),
+            _ => Move::all()
+                .into_iter()
+                .find(|direction| format!("{:?}", direction).eq_ignore_ascii_case(text)),
+        };
+        direction.ok_or_else(|| ParseMoveError(s.to_string()))
+    }
+}
+
+/// Boards are compared by their grid alone, so that checking whether a move changed anything isn't
+/// thrown off by the score. Use [Board::same_state] to compare the score as well.
+impl<const W: usize, const H: usize> PartialEq for Board<W, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows
+    }
+}
+
+impl<const W: usize, const H: usize> Eq for Board<W, H> {}
+
+/// Like equality, only the grid is hashed, so that boards reached with different scores share an
+/// entry in a transposition table.
+impl<const W: usize, const H: usize> Hash for Board<W, H> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.rows.hash(state);
+    }
+}
+
+/// The cell at `(x, y)`, which is column `x` of row `y`. Panics if either is off the board.
+impl<const W: usize, const H: usize> Index<(usize, usize)> for Board<W, H> {
+    type Output = Option<Square>;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        &self.rows[y][x]
+    }
+}
+
+impl<const W: usize, const H: usize> IndexMut<(usize, usize)> for Board<W, H> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        &mut self.rows[y][x]
+    }
+}
+
+/// A plain text version of the board, with no colours or escape codes, for logs and tests. Every
+/// cell has a space before it, so that even the widest tiles are kept apart from their neighbours.
+impl<const W: usize, const H: usize> fmt::Display for Board<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = MAX_DIGIT_WIDTH as usize;
+        for (row_id, row) in self.rows.iter().enumerate() {
+            if row_id != 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                match cell {
+                    Some(cell) => write!(f, " {:>width$}", cell.text())?,
+                    None => write!(f, " {:>width$}", ".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the format written by [Board]'s `Display` impl: one line per row, with cells separated by
+/// whitespace, and `.` or `-` for an empty cell. Tiles are numbers, or powers of two such as
+/// `2^40`. Blank lines are ignored.
+impl<const W: usize, const H: usize> FromStr for Board<W, H> {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        if lines.len() != H {
+            return Err(ParseBoardError::WrongRowCount {
+                expected: H,
+                found: lines.len(),
+            });
+        }
+
+        let mut board = Board::new();
+        for (row_id, (line, row)) in zip(lines, &mut board.rows).enumerate() {
+            let cells = line.split_whitespace().collect::<Vec<_>>();
+            if cells.len() != W {
+                return Err(ParseBoardError::WrongColumnCount {
+                    row: row_id,
+                    expected: W,
+                    found: cells.len(),
+                });
+            }
+
+            for (cell, output) in zip(cells, row) {
+                if cell == "." || cell == "-" {
+                    continue;
+                }
+                if let Some(exponent) = cell.strip_prefix("2^") {
+                    let square = exponent
+                        .parse::<u16>()
+                        .ok()
+                        .filter(|exponent| (1..=256).contains(exponent))
+                        .ok_or_else(|| ParseBoardError::InvalidCell(cell.to_string()))?;
+                    *output = Some(Square((square - 1) as u8));
+                    continue;
+                }
+                let value = cell
+                    .parse::<u32>()
+                    .map_err(|_| ParseBoardError::InvalidCell(cell.to_string()))?;
+                if !value.is_power_of_two() {
+                    return Err(ParseBoardError::NotPowerOfTwo(value));
+                }
+                *output =
+                    Some(Square::from_value(value).ok_or(ParseBoardError::OutOfRange(value))?);
+            }
+        }
+
+        Ok(board)
+    }
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoardError::WrongRowCount { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+            ParseBoardError::WrongColumnCount {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {} cells in row {}, found {}",
+                expected,
+                row + 1,
+                found
+            ),
+            ParseBoardError::InvalidCell(cell) => write!(f, "{:?} is not a valid cell", cell),
+            ParseBoardError::NotPowerOfTwo(value) => {
+                write!(f, "{} is not a power of two", value)
+            }
+            ParseBoardError::OutOfRange(value) => write!(
+                f,
+                "{} is outside the range of tiles, 2 to {}",
+                value, MAX_TILE_VALUE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseBoardError {}
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} is not a move", self.0)
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+impl<const W: usize, const H: usize> Default for Board<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> Board<W, H> {
     /// Create a new default board.
     pub fn new() -> Self {
         Board {
-            rows: [[None; 4]; 4],
+            rows: [[None; W]; H],
+            score: 0,
         }
     }
 
-    fn coord_iter(direction: Move, offset: usize) -> impl Iterator<Item = (usize, usize)> {
-        let steps = match direction {
-            Move::Up | Move::Left => [0, 1, 2, 3].into_iter(),
-            Move::Down | Move::Right => [3, 2, 1, 0].into_iter(),
-        };
-        steps.map(move |i| match direction {
-            Move::Up | Move::Down => (offset, i),
-            Move::Left | Move::Right => (i, offset),
+    /// A board with the given tiles on it, each at `(x, y)`, and nothing else. Panics if any of
+    /// them is off the board.
+    pub fn from_tiles(tiles: impl IntoIterator<Item = ((usize, usize), Square)>) -> Self {
+        let mut board = Board::new();
+        for ((x, y), square) in tiles {
+            assert!(
+                x < W && y < H,
+                "({}, {}) is off a board {} squares across and {} down",
+                x,
+                y,
+                W,
+                H
+            );
+            board.rows[y][x] = Some(square);
+        }
+        board
+    }
+
+    /// The total value of every merge made so far.
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// The numbers on every tile added together. Unlike the score, which only goes up with merges,
+    /// this goes up with every new tile, so it's a measure of the board itself.
+    pub fn board_sum(&self) -> u32 {
+        self.rows
+            .iter()
+            .flatten()
+            .flatten()
+            .fold(0, |sum, square| sum.saturating_add(square.value()))
+    }
+
+    /// The highest tile on the board, if there are any tiles at all.
+    pub fn max_tile(&self) -> Option<Square> {
+        self.rows.iter().flatten().flatten().copied().max()
+    }
+
+    /// Whether every cell on the board has a tile in it.
+    pub fn is_full(&self) -> bool {
+        self.rows.iter().flatten().all(Option::is_some)
+    }
+
+    /// How many cells on the board are empty.
+    pub fn count_empty(&self) -> usize {
+        self.rows
+            .iter()
+            .flatten()
+            .filter(|cell| cell.is_none())
+            .count()
+    }
+
+    /// How far the rows and columns are from each running in a single direction, for judging
+    /// positions. For each row and column, the rises and the falls between neighbouring squares
+    /// are added up separately, and whichever is smaller counts against the board. Tiles are
+    /// counted by their exponent, 0 for a 2 and so on, as are empty squares, which count as 0.
+    ///
+    /// This is a penalty, so it's never positive: 0 means every row and column only ever rises or
+    /// only ever falls, and the further below 0, the more muddled the board.
+    pub fn monotonicity(&self) -> i32 {
+        let columns = self.transpose();
+        let lanes = self.rows.iter().map(|row| row.as_slice());
+        let mut penalty = 0;
+        for lane in lanes.chain(columns.rows.iter().map(|column| column.as_slice())) {
+            let exponents = lane
+                .iter()
+                .map(|cell| cell.map_or(0, |cell| i32::from(cell.0)));
+            let (mut increase, mut decrease) = (0, 0);
+            for (first, second) in zip(exponents.clone(), exponents.skip(1)) {
+                if first < second {
+                    increase += second - first;
+                } else {
+                    decrease += first - second;
+                }
+            }
+            penalty += increase.min(decrease);
+        }
+        -penalty
+    }
+
+    /// How different neighbouring tiles are, for judging positions. The difference between the
+    /// exponents of each pair of tiles next to each other in a row or column, skipping over empty
+    /// squares, counts against the board.
+    ///
+    /// This is a penalty, so it's never positive: 0 means every tile matches the ones beside it,
+    /// and the further below 0, the harder the tiles are to bring together.
+    pub fn smoothness(&self) -> i32 {
+        let columns = self.transpose();
+        let lanes = self.rows.iter().map(|row| row.as_slice());
+        let mut penalty = 0;
+        for lane in lanes.chain(columns.rows.iter().map(|column| column.as_slice())) {
+            let tiles = lane.iter().flatten().map(|cell| i32::from(cell.0));
+            for (first, second) in zip(tiles.clone(), tiles.skip(1)) {
+                penalty += (first - second).abs();
+            }
+        }
+        -penalty
+    }
+
+    /// The tile at column `x` of row `y`, if there is one. Anything off the board is empty.
+    pub fn get(&self, x: usize, y: usize) -> Option<Square> {
+        *self.rows.get(y)?.get(x)?
+    }
+
+    /// Every tile on the board as `(x, y, tile)`, a row at a time from the top.
+    pub fn tiles(&self) -> impl Iterator<Item = (usize, usize, Square)> + '_ {
+        self.rows.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(move |(x, cell)| cell.map(|cell| (x, y, cell)))
         })
     }
 
-    fn collapse(input: impl Iterator<Item = Option<Square>>) -> impl Iterator<Item = Square> {
-        let only_cells = input.filter_map(|x| x);
-        struct Collapser<I> {
+    /// Slide the tiles of a lane together, merging neighbours that the rule allows. Each tile that
+    /// comes out is paired with whether it was made by a merge.
+    fn collapse<M: MergeRule>(
+        input: impl Iterator<Item = Option<Square>>,
+    ) -> impl Iterator<Item = (Square, bool)> {
+        let only_cells = input.flatten();
+        struct Collapser<I, M> {
             inner: I,
             last_seen: Option<Square>,
+            rule: PhantomData<M>,
         }
 
-        impl<I: Iterator<Item = Square>> Iterator for Collapser<I> {
-            type Item = Square;
+        impl<I: Iterator<Item = Square>, M: MergeRule> Iterator for Collapser<I, M> {
+            type Item = (Square, bool);
 
             fn next(&mut self) -> Option<Self::Item> {
                 if let Some(last) = self.last_seen.take() {
                     match self.inner.next() {
-                        Some(item) if item == last => Some(item.inc()),
+                        Some(item) if M::can_merge(last, item) => {
+                            Some((M::merge(last, item), true))
+                        }
                         Some(other) => {
                             self.last_seen = Some(other);
-                            Some(last)
+                            Some((last, false))
                         }
-                        None => Some(last),
+                        None => Some((last, false)),
                     }
                 } else {
                     match self.inner.next() {
@@ -104,205 +540,850 @@ impl Board {
                             self.last_seen = Some(next);
                             self.next()
                         }
-                        None => return None,
+                        None => None,
                     }
                 }
             }
         }
 
-        Collapser {
+        Collapser::<_, M> {
             inner: only_cells,
             last_seen: None,
+            rule: PhantomData,
         }
     }
 
-    pub fn apply_move(self, direction: Move) -> Self {
+    /// Collapse a single lane towards its start, merging neighbours that the rule allows. Also
+    /// returns where the merges were and the score gained from them.
+    fn collapse_lane<M: MergeRule, const L: usize>(
+        lane: [Option<Square>; L],
+    ) -> ([Option<Square>; L], [bool; L], u32) {
+        let mut output = [None; L];
+        let mut merges = [false; L];
+        let mut score = 0;
+        for (slot, (cell, merged)) in zip(
+            zip(&mut output, &mut merges),
+            Self::collapse::<M>(lane.into_iter()),
+        ) {
+            *slot.0 = Some(cell);
+            if merged {
+                *slot.1 = true;
+                score += M::value(cell);
+            }
+        }
+        (output, merges, score)
+    }
+
+    /// Swap the rows and columns of the board, reflecting it along the diagonal from the top left.
+    pub fn transpose(self) -> Board<H, W> {
         let mut output = Board {
-            rows: [[None; 4]; 4],
+            score: self.score,
+            ..Board::new()
         };
-        for offset in 0..4 {
-            let existing = Self::coord_iter(direction, offset).map(|(x, y)| self.rows[y][x]);
-            let collapsed = Self::collapse(existing);
-            let mut write_coords = Self::coord_iter(direction, offset);
-            for cell in collapsed {
-                let (x, y) = write_coords.next().expect("Too many cells post-collapse");
-                output.rows[y][x] = Some(cell);
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                output.rows[x][y] = cell;
             }
         }
+        output
+    }
+
+    /// Flip the board left-to-right.
+    fn mirror(mut self) -> Self {
+        for row in &mut self.rows {
+            row.reverse();
+        }
+        self
+    }
+
+    /// Turn the board a quarter turn clockwise.
+    pub fn rotate_cw(self) -> Board<H, W> {
+        self.transpose().mirror()
+    }
+
+    /// Turn the board a quarter turn anticlockwise.
+    pub fn rotate_ccw(self) -> Board<H, W> {
+        self.mirror().transpose()
+    }
+
+    /// How many lanes the tiles slide along for the given move, and how many squares long each
+    /// one is.
+    fn lanes(direction: Move) -> (usize, usize) {
+        match direction {
+            Move::Left | Move::Right => (H, W),
+            Move::Up | Move::Down => (W, H),
+        }
+    }
 
+    /// Where the square at `index` along `lane` of the given move is on the board, as a row and
+    /// column. Lanes are counted from the top or the left, and squares from the edge the tiles
+    /// slide towards.
+    fn lane_position(direction: Move, lane: usize, index: usize) -> (usize, usize) {
+        match direction {
+            Move::Left => (lane, index),
+            Move::Right => (lane, W - 1 - index),
+            Move::Up => (index, lane),
+            Move::Down => (H - 1 - index, lane),
+        }
+    }
+
+    /// The squares along one lane of the given move, in the order the tiles slide. `L` must be the
+    /// length of the move's lanes.
+    fn lane<const L: usize>(&self, direction: Move, lane: usize) -> [Option<Square>; L] {
+        std::array::from_fn(|index| {
+            let (y, x) = Self::lane_position(direction, lane, index);
+            self.rows[y][x]
+        })
+    }
+
+    /// Put back the squares along one lane of the given move, as taken out by [Board::lane].
+    fn set_lane<const L: usize>(
+        &mut self,
+        direction: Move,
+        lane: usize,
+        cells: [Option<Square>; L],
+    ) {
+        for (index, cell) in cells.into_iter().enumerate() {
+            let (y, x) = Self::lane_position(direction, lane, index);
+            self.rows[y][x] = cell;
+        }
+    }
+
+    pub fn apply_move(self, direction: Move) -> Self {
+        self.apply_move_detailed(direction).board
+    }
+
+    /// Apply a move, returning the new board along with how much the move added to the score.
+    pub fn apply_move_scored(self, direction: Move) -> (Self, u32) {
+        let outcome = self.apply_move_detailed(direction);
+        (outcome.board, outcome.score_gained)
+    }
+
+    /// Apply a move, reporting what it did along with the new board.
+    pub fn apply_move_detailed(self, direction: Move) -> MoveOutcome<W, H> {
+        self.apply_move_with_rule::<Doubling>(direction)
+    }
+
+    /// Apply a move with tiles merging by the given rule instead of the usual doubling, reporting
+    /// what it did along with the new board.
+    pub fn apply_move_with_rule<M: MergeRule>(self, direction: Move) -> MoveOutcome<W, H> {
+        let mut outcome = MoveOutcome {
+            board: self,
+            moved: false,
+            merged_tiles: 0,
+            merged_cells: [[false; W]; H],
+            score_gained: 0,
+        };
+        // Rows and columns are different lengths unless the board is square
+        for lane in 0..Self::lanes(direction).0 {
+            match direction {
+                Move::Left | Move::Right => {
+                    self.collapse_lane_into::<M, W>(direction, lane, &mut outcome)
+                }
+                Move::Up | Move::Down => {
+                    self.collapse_lane_into::<M, H>(direction, lane, &mut outcome)
+                }
+            }
+        }
+        outcome.board.score += outcome.score_gained;
+        outcome
+    }
+
+    /// Collapse one lane of the given move, `L` squares long, and record what happened in
+    /// `outcome`. The score on the board is left for the caller to add up.
+    fn collapse_lane_into<M: MergeRule, const L: usize>(
+        &self,
+        direction: Move,
+        lane: usize,
+        outcome: &mut MoveOutcome<W, H>,
+    ) {
+        let cells = self.lane::<L>(direction, lane);
+        // A tile slides if there is a gap anywhere in front of it
+        let slid = cells
+            .iter()
+            .skip_while(|cell| cell.is_some())
+            .any(Option::is_some);
+        let (collapsed, merges, score) = Self::collapse_lane::<M, L>(cells);
+        outcome.board.set_lane(direction, lane, collapsed);
+        outcome.moved |= slid || merges.contains(&true);
+        for (index, _) in merges.iter().enumerate().filter(|(_, &merged)| merged) {
+            let (y, x) = Self::lane_position(direction, lane, index);
+            outcome.merged_cells[y][x] = true;
+            outcome.merged_tiles += 1;
+        }
+        outcome.score_gained += score;
+    }
+
+    /// The board part way through sliding its tiles for the given move, `step` out of `steps` of
+    /// the way there. Tiles keep their old values, as merges only happen once they've arrived.
+    #[cfg(feature = "tui")]
+    fn slide_frame(self, direction: Move, step: u32, steps: u32) -> Self {
+        let mut output = self;
+        for lane in 0..Self::lanes(direction).0 {
+            match direction {
+                Move::Left | Move::Right => output.set_lane(
+                    direction,
+                    lane,
+                    Self::slide_lane::<W>(self.lane(direction, lane), step, steps),
+                ),
+                Move::Up | Move::Down => output.set_lane(
+                    direction,
+                    lane,
+                    Self::slide_lane::<H>(self.lane(direction, lane), step, steps),
+                ),
+            }
+        }
+        output
+    }
+
+    /// A single lane part way through sliding towards its start, as in [Board::slide_frame].
+    #[cfg(feature = "tui")]
+    fn slide_lane<const L: usize>(
+        lane: [Option<Square>; L],
+        step: u32,
+        steps: u32,
+    ) -> [Option<Square>; L] {
+        let mut output = [None; L];
+        // Where the next tile will end up, and the tile waiting there to be merged into
+        let mut next_slot = 0;
+        let mut waiting = None;
+        for (from, cell) in lane.iter().enumerate() {
+            let Some(cell) = *cell else { continue };
+            let to = if waiting == Some(cell) {
+                waiting = None;
+                next_slot - 1
+            } else {
+                waiting = Some(cell);
+                next_slot += 1;
+                next_slot - 1
+            };
+            let distance = (from - to) as u32 * step / steps;
+            output[from - distance as usize] = Some(cell);
+        }
         output
     }
 
-    /// Attempts to add a new square to the board.
-    pub fn add_square(&mut self, rng: &mut impl Rng) {
-        let coords = (0..4).flat_map(|y| (0..4).map(move |x| (x, y)));
+    /// Describe what the given move does to each lane of the board that it changes, e.g.
+    /// `Left row 2: [4,4,2,2] → [8,4]: merged 4+4→8 and 2+2→4`.
+    pub fn explain_move(self, direction: Move) -> Vec<String> {
+        (0..Self::lanes(direction).0)
+            .filter_map(|lane_id| match direction {
+                Move::Left | Move::Right => {
+                    Self::explain_lane::<W>(direction, lane_id, self.lane(direction, lane_id))
+                }
+                Move::Up | Move::Down => {
+                    Self::explain_lane::<H>(direction, lane_id, self.lane(direction, lane_id))
+                }
+            })
+            .collect()
+    }
+
+    /// Describe what the given move does to one of its lanes, as in [Board::explain_move], or
+    /// `None` if it leaves the lane alone.
+    fn explain_lane<const L: usize>(
+        direction: Move,
+        lane_id: usize,
+        lane: [Option<Square>; L],
+    ) -> Option<String> {
+        let lane_kind = match direction {
+            Move::Up | Move::Down => "column",
+            Move::Left | Move::Right => "row",
+        };
+        let (collapsed, _, _) = Self::collapse_lane::<Doubling, L>(lane);
+        if collapsed == lane {
+            return None;
+        }
+
+        // Every tile after the move either came across on its own, or is two of the tiles from
+        // before the move merged together.
+        let mut before = lane.iter().flatten().copied();
+        let mut merges = Vec::new();
+        for &after in collapsed.iter().flatten() {
+            let first = before
+                .next()
+                .expect("Collapsed lane has more tiles than before");
+            if first != after {
+                before.next();
+                merges.push(format!(
+                    "{}+{}→{}",
+                    first.value(),
+                    first.value(),
+                    after.value()
+                ));
+            }
+        }
+
+        let mut before = lane
+            .iter()
+            .map(|cell| match cell {
+                Some(cell) => cell.value().to_string(),
+                None => ".".to_string(),
+            })
+            .collect::<Vec<_>>();
+        let mut after = collapsed
+            .iter()
+            .flatten()
+            .map(|cell| cell.value().to_string())
+            .collect::<Vec<_>>();
+        if matches!(direction, Move::Right | Move::Down) {
+            // Show the lanes the way round they appear on the screen
+            before.reverse();
+            after.reverse();
+        }
+        let summary = if merges.is_empty() {
+            "slid".to_string()
+        } else {
+            format!("merged {}", merges.join(" and "))
+        };
+        Some(format!(
+            "{:?} {} {}: [{}] → [{}]: {}",
+            direction,
+            lane_kind,
+            lane_id + 1,
+            before.join(","),
+            after.join(","),
+            summary
+        ))
+    }
+
+    /// Attempts to add a new square to the board, which is a 4 with probability
+    /// [DEFAULT_FOUR_PROBABILITY] and otherwise a 2. Returns where the tile went as `(x, y)`, or
+    /// `None` if the board was full.
+    pub fn add_square(&mut self, rng: &mut impl Rng) -> Option<(usize, usize)> {
+        self.add_square_from(rng, &DEFAULT_SPAWNS)
+    }
+
+    /// Attempts to add a new square to the board, which is a 4 with probability
+    /// `four_probability` and otherwise a 2. The probability must be between 0 and 1. Returns where
+    /// the tile went as `(x, y)`, or `None` if the board was full.
+    pub fn add_square_with_odds(
+        &mut self,
+        rng: &mut impl Rng,
+        four_probability: f64,
+    ) -> Option<(usize, usize)> {
+        assert!(
+            (0.0..=1.0).contains(&four_probability),
+            "The chance of a 4 must be between 0 and 1, not {}",
+            four_probability
+        );
+        self.add_square_from(
+            rng,
+            &[
+                (Square(0), 1.0 - four_probability),
+                (Square(1), four_probability),
+            ],
+        )
+    }
+
+    /// Attempts to add a new square to the board, picked from `spawns` with chances in proportion
+    /// to the weight given for each tile. The weights must not be negative, and must not all be
+    /// zero. Returns where the tile went as `(x, y)`, or `None` if the board was full.
+    pub fn add_square_from(
+        &mut self,
+        rng: &mut impl Rng,
+        spawns: &[(Square, f64)],
+    ) -> Option<(usize, usize)> {
+        check_spawns(spawns);
+        if self.is_full() {
+            return None;
+        }
+        let coords = (0..H).flat_map(|y| (0..W).map(move |x| (x, y)));
         let free_spaces = coords
             .filter(|&(x, y)| self.rows[y][x].is_none())
             .collect::<Vec<_>>();
-        if free_spaces.is_empty() {
-            return;
-        }
 
         let space_choice = rng.gen_range(0..free_spaces.len());
-        let new_cell = if rng.gen() { Square(1) } else { Square(0) };
+        // Working back from the last tile means the usual 2 or 4 takes a single coin flip for the
+        // 4, so games from the same seed come out the same as they always have
+        let mut remaining: f64 = spawns.iter().map(|&(_, weight)| weight).sum();
+        let mut new_cell = spawns[0].0;
+        for &(square, weight) in spawns[1..].iter().rev() {
+            if weight > 0.0 && rng.gen_bool((weight / remaining).min(1.0)) {
+                new_cell = square;
+                break;
+            }
+            remaining -= weight;
+        }
         let (x, y) = free_spaces[space_choice];
         self.rows[y][x] = Some(new_cell);
+        Some((x, y))
+    }
+
+    /// Whether the given move would change the board. Cheaper than applying the move, as it stops
+    /// at the first tile that would slide or merge.
+    pub fn can_move(&self, direction: Move) -> bool {
+        let (lanes, length) = Self::lanes(direction);
+        (0..lanes).any(|lane| {
+            let mut seen_gap = false;
+            let mut last_tile = None;
+            for index in 0..length {
+                let (y, x) = Self::lane_position(direction, lane, index);
+                match self.rows[y][x] {
+                    None => seen_gap = true,
+                    // A tile after a gap slides into it, and one after an equal tile merges
+                    Some(cell) if seen_gap || last_tile == Some(cell) => return true,
+                    cell => last_tile = cell,
+                }
+            }
+            false
+        })
+    }
+
+    /// The moves that would change the board.
+    pub fn available_moves(&self) -> impl Iterator<Item = Move> {
+        let board = *self;
+        Move::all()
+            .into_iter()
+            .filter(move |&direction| board.can_move(direction))
+    }
+
+    /// Whether the board has a tile at least as big as the target.
+    pub fn has_won(&self, target: Square) -> bool {
+        self.rows
+            .iter()
+            .flatten()
+            .flatten()
+            .any(|&cell| cell >= target)
+    }
+
+    /// Whether no move can change the board any more.
+    pub fn is_game_over(&self) -> bool {
+        self.is_game_over_with_rule::<Doubling>()
+    }
+
+    /// Whether no move can change the board any more, with tiles merging by the given rule.
+    pub fn is_game_over_with_rule<M: MergeRule>(&self) -> bool {
+        if !self.is_full() {
+            return false;
+        }
+
+        // On a full board nothing can slide, so a move can only do anything by merging a pair of
+        // neighbours. Either one could be the one sliding into the other.
+        let mergeable = |a: Option<Square>, b: Option<Square>| match (a, b) {
+            (Some(a), Some(b)) => M::can_merge(a, b) || M::can_merge(b, a),
+            _ => false,
+        };
+        let across = self
+            .rows
+            .iter()
+            .any(|row| row.windows(2).any(|pair| mergeable(pair[0], pair[1])));
+        let down = self
+            .rows
+            .windows(2)
+            .any(|pair| zip(pair[0], pair[1]).any(|(above, below)| mergeable(above, below)));
+        !(across || down)
+    }
+
+    /// Whether two boards have the same tiles in the same places and the same score. Unlike `==`,
+    /// which only looks at the tiles, this tells apart the same grid reached in different ways.
+    pub fn same_state(&self, other: &Board<W, H>) -> bool {
+        self.rows == other.rows && self.score == other.score
     }
+
+    /// Whether this board exactly matches the goal arrangement of a puzzle.
+    pub fn is_solved_against(&self, goal: &Board<W, H>) -> bool {
+        self.rows == goal.rows
+    }
+
+    /// The board on one line with no spaces, such as `2,4,.,./.,.,.,./.,.,.,./.,.,.,.`, so that it
+    /// can be given as a single argument on the command line. Rows are separated by `/` and cells
+    /// by `,`.
+    pub fn to_compact(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map_or(".".to_string(), Square::text))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Reads the format written by [Board::to_compact]. Anything the `FromStr` impl accepts inside
+    /// a row, such as `-` for an empty cell, is accepted here too.
+    pub fn from_compact(s: &str) -> Result<Self, ParseBoardError> {
+        s.replace('/', "\n").replace(',', " ").parse()
+    }
+}
+
+impl<const W: usize, const H: usize> MoveOutcome<W, H> {
+    /// The tiles made by merges when the board was moved in `direction`, in the order they were
+    /// made: lane by lane from the top or the left, and along each lane from the edge the tiles
+    /// slid towards.
+    pub fn merged_squares(&self, direction: Move) -> Vec<Square> {
+        let (lanes, length) = Board::<W, H>::lanes(direction);
+        (0..lanes)
+            .flat_map(|lane| (0..length).map(move |index| (lane, index)))
+            .map(|(lane, index)| Board::<W, H>::lane_position(direction, lane, index))
+            .filter(|&(y, x)| self.merged_cells[y][x])
+            .filter_map(|(y, x)| self.board.rows[y][x])
+            .collect()
+    }
+}
+
+/// Panics unless `spawns` is a usable set of tiles to add to the board, with no negative weights
+/// and at least one that isn't zero.
+fn check_spawns(spawns: &[(Square, f64)]) {
+    assert!(
+        spawns
+            .iter()
+            .all(|&(_, weight)| weight.is_finite() && weight >= 0.0),
+        "Spawn weights must be finite and not negative, not {:?}",
+        spawns
+    );
+    assert!(
+        spawns.iter().any(|&(_, weight)| weight > 0.0),
+        "At least one tile must have a chance of being added, not {:?}",
+        spawns
+    );
+}
+
+/// A short reminder of which tiles are added after each move and how often, such as
+/// `spawn: 2/4 90/10` for the usual game. Each chance is a whole percentage of the total weight.
+pub fn spawn_label(spawns: &[(Square, f64)]) -> String {
+    let total = spawns.iter().map(|&(_, weight)| weight).sum::<f64>();
+    let tiles = spawns
+        .iter()
+        .map(|(tile, _)| tile.value().to_string())
+        .collect::<Vec<_>>();
+    let chances = spawns
+        .iter()
+        .map(|&(_, weight)| format!("{:.0}", 100.0 * weight / total))
+        .collect::<Vec<_>>();
+    format!("spawn: {} {}", tiles.join("/"), chances.join("/"))
+}
+
+/// Keeps each board on the screen for a minimum amount of time, so that a burst of queued moves
+/// still shows every intermediate state instead of skipping straight to the end.
+#[derive(Copy, Clone, Debug)]
+pub struct Pacer {
+    min_frame_time: Duration,
+    last_frame: Option<Instant>,
+}
+
+/// Measures how long a game has been played for, leaving out any time it spent stopped. All times
+/// are passed in rather than read from the system, so that it works anywhere.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Clock {
+    /// The time counted before the clock was last started
+    banked: Duration,
+    /// When the clock was last started, if it's running
+    running_since: Option<Instant>,
+}
+
+/// The numbers shown in the header above the board.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub score: u32,
+    /// The numbers on every tile on the board added together
+    pub board_sum: u32,
+    pub best: u32,
+    pub moves: u32,
+    /// How many more moves can be made, if they're limited
+    pub moves_left: Option<u32>,
+    /// How long the game has been played for, if it's to be shown
+    pub elapsed: Option<Duration>,
+    /// How long there is left to play, if the game is against the clock
+    pub time_left: Option<Duration>,
+    /// Whether new tiles have been turned off, so that the game isn't being played for real
+    pub no_spawns: bool,
+    /// What the last merge of the most recent move was multiplied by, if merges are scored as
+    /// combos
+    pub combo: Option<u32>,
+    /// Which tiles are added after each move and how often, as a [spawn_label], if it isn't the
+    /// usual game
+    pub spawns: Option<String>,
+}
+
+/// What to draw around and between the squares of the board.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Borders {
+    /// Let the tiles float, with nothing between them
+    #[default]
+    None,
+    /// Box drawing characters, like ┌─┬─┐
+    Unicode,
+    /// Plain characters, like +-+-+, for terminals without Unicode support
+    Ascii,
 }
 
-/// A wrapper around crossterm + stdout that puts boards on the screen
-pub struct Renderer<Output: Write> {
-    output: Output,
-    size: (u16, u16),
-    cursor_row: u16,
-    /// What's currently on the screen, if anything
-    old_board: Option<Board>,
+/// Where the number goes in a square that's wider than it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Justify {
+    Left,
+    /// In the middle, a space further left when it can't be exactly in the middle
+    Center,
+    /// Against the right edge, so that the digits of tiles in a column line up
+    #[default]
+    Right,
 }
 
-impl<Output: Write> Renderer<Output> {
-    /// Create a renderer from a stdout handle.
-    pub fn new(mut output: Output) -> crossterm::Result<Self> {
-        // Before we enter raw mode, push the screen down 4 rows so that we have space to play our
-        // game at the bottom of the screen.
-        for _ in 0..=SIZE {
-            writeln!(output)?;
+impl Borders {
+    /// The character to put between squares on the same row, if any.
+    fn vertical(self) -> Option<char> {
+        match self {
+            Borders::None => None,
+            Borders::Unicode => Some('│'),
+            Borders::Ascii => Some('|'),
         }
-        crossterm::terminal::enable_raw_mode()?;
-        let mut renderer = Renderer {
-            output,
-            size: (0, 0),
-            cursor_row: SIZE + 1,
-            old_board: None,
-        };
+    }
 
-        renderer.output.queue(crossterm::cursor::Hide)?;
+    /// The characters for the given line between rows, counting the top edge as line 0 and the
+    /// bottom edge as line `lines`: the left end, the filler, the junctions and the right end.
+    fn horizontal(self, line: usize, lines: usize) -> Option<[char; 4]> {
+        match self {
+            Borders::None => None,
+            Borders::Unicode if line == 0 => Some(['┌', '─', '┬', '┐']),
+            Borders::Unicode if line == lines => Some(['└', '─', '┴', '┘']),
+            Borders::Unicode => Some(['├', '─', '┼', '┤']),
+            Borders::Ascii => Some(['+', '-', '+', '+']),
+        }
+    }
+}
 
-        let size = crossterm::terminal::size()?;
-        renderer.resize(size)?;
+/// Where the squares and borders of the board go, shared by the renderer and plain text snapshots
+/// so that the two always agree.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Layout<const W: usize, const H: usize> {
+    /// How many characters across each square is
+    cell_width: u16,
+    borders: Borders,
+    /// Where the number goes in each square
+    justify: Justify,
+    /// Whether squares show the exponent they hold, 0 for a 2 and so on, rather than the number
+    exponents: bool,
+    /// Whether numbers are written in big characters, several rows tall. Each square then has
+    /// room for one character fewer than `cell_width`, with a column spare either side.
+    big: bool,
+    /// Whether squares are squeezed down to two columns, for the narrowest windows. Each shows
+    /// its exponent as a single hexadecimal digit, 0 for a 2 up to f for 65536, whatever
+    /// `cell_width` and `exponents` say.
+    compact: bool,
+}
 
-        Ok(renderer)
+impl<const W: usize, const H: usize> Default for Layout<W, H> {
+    fn default() -> Self {
+        Layout {
+            cell_width: MAX_DIGIT_WIDTH,
+            borders: Borders::None,
+            justify: Justify::Right,
+            exponents: false,
+            big: false,
+            compact: false,
+        }
     }
+}
 
-    /// Handle a resize event - note that to finish handling the resize event you will also need to
-    /// redraw the board.
-    pub fn resize(&mut self, new_size: (u16, u16)) -> crossterm::Result<()> {
-        self.size = new_size;
-        self.old_board = None;
+impl<const W: usize, const H: usize> Layout<W, H> {
+    /// How many squares there are across the board
+    #[cfg(feature = "tui")]
+    const COLUMNS: u16 = W as u16;
+    /// How many squares there are down the board
+    #[cfg(feature = "tui")]
+    const ROWS: u16 = H as u16;
 
-        Ok(())
+    /// How many columns across each square is on the screen.
+    fn cell_columns(&self) -> u16 {
+        if self.big {
+            (self.cell_width - 1) * font::GLYPH_WIDTH + 2
+        } else if self.compact {
+            2
+        } else {
+            self.cell_width
+        }
     }
 
-    fn draw_cell(&mut self, cell: Square) -> crossterm::Result<()> {
-        let (bg, is_dark) = cell.color();
-        self.output
-            .queue(crossterm::style::SetBackgroundColor(bg))?;
-        if is_dark {
-            self.output.queue(crossterm::style::SetForegroundColor(
-                crossterm::style::Color::Black,
-            ))?;
+    /// How many rows down each square is on the screen.
+    fn cell_height(&self) -> u16 {
+        if self.big {
+            font::GLYPH_HEIGHT
         } else {
-            self.output
-                .queue(crossterm::style::SetForegroundColor(
-                    crossterm::style::Color::White,
-                ))?
-                .queue(crossterm::style::SetAttribute(
-                    crossterm::style::Attribute::Bold,
-                ))?;
-        }
-        write!(self.output, "{:5}", 2 << cell.0)?;
-        self.output.queue(crossterm::style::ResetColor)?;
-        Ok(())
+            1
+        }
     }
 
-    /// Mark the game as over
-    pub fn lose(&mut self) -> crossterm::Result<()> {
-        let string = "Game over";
-        self.output
-            .queue(crossterm::cursor::MoveDown(1 + SIZE - self.cursor_row))?
-            .queue(crossterm::cursor::MoveToColumn(
-                (SIZE * MAX_DIGIT_WIDTH - string.len() as u16) / 2,
-            ))?;
-        write!(self.output, "{}", string)?;
-        self.output.flush()
-    }
-
-    /// Draw the current board on the screen.
-    pub fn draw_board(&mut self, board: &Board) -> crossterm::Result<()> {
-        if self.size.0 < SIZE * MAX_DIGIT_WIDTH || self.size.1 < SIZE {
-            return Err(crossterm::ErrorKind::new(
-                ErrorKind::Other,
-                "Window too small to draw the game board",
-            ));
-        }
-
-        if let Some(old_board) = self.old_board {
-            for (row_id, rows) in zip(old_board.rows, board.rows).enumerate() {
-                for (col_id, (old, new)) in zip(rows.0, rows.1).enumerate() {
-                    if old == new {
-                        continue;
-                    }
+    /// How many characters across the board is, including any borders.
+    #[cfg(feature = "tui")]
+    fn board_width(&self) -> u16 {
+        match self.borders {
+            Borders::None => Self::COLUMNS * self.cell_columns(),
+            _ => Self::COLUMNS * (self.cell_columns() + 1) + 1,
+        }
+    }
 
-                    let screen_row = row_id as u16;
-                    let screen_col = MAX_DIGIT_WIDTH * (col_id as u16);
-
-                    match screen_row.cmp(&self.cursor_row) {
-                        Ordering::Less => self
-                            .output
-                            .queue(crossterm::cursor::MoveUp(self.cursor_row - screen_row))?,
-                        Ordering::Equal => &mut self.output,
-                        Ordering::Greater => self
-                            .output
-                            .queue(crossterm::cursor::MoveDown(screen_row - self.cursor_row))?,
-                    };
-                    self.cursor_row = screen_row;
-
-                    self.output
-                        .queue(crossterm::cursor::MoveToColumn(screen_col))?;
-                    if let Some(cell) = new {
-                        self.draw_cell(cell)?;
-                    } else {
-                        write!(self.output, "     ")?; // Deliberately write spaces instead of move
-                    }
-                }
-            }
+    /// How many rows the board takes up, including any borders.
+    #[cfg(feature = "tui")]
+    fn board_height(&self) -> u16 {
+        match self.borders {
+            Borders::None => Self::ROWS * self.cell_height(),
+            _ => Self::ROWS * (self.cell_height() + 1) + 1,
+        }
+    }
+
+    /// Where the given square's top left corner is, as a row and column from the board's top left
+    /// corner.
+    #[cfg(feature = "tui")]
+    fn cell_position(&self, row_id: usize, col_id: usize) -> (u16, u16) {
+        let (row_id, col_id) = (row_id as u16, col_id as u16);
+        let (columns, height) = (self.cell_columns(), self.cell_height());
+        match self.borders {
+            Borders::None => (row_id * height, columns * col_id),
+            _ => (row_id * (height + 1) + 1, (columns + 1) * col_id + 1),
+        }
+    }
+
+    /// The row that the border line above the given row of squares is on, or the one below the
+    /// last row when given `H`.
+    #[cfg(feature = "tui")]
+    fn border_row(&self, line: usize) -> u16 {
+        line as u16 * (self.cell_height() + 1)
+    }
+
+    /// The border line above the given row of squares, or below the last row when given `H`, if
+    /// there are borders.
+    fn border_line(&self, line: usize) -> Option<String> {
+        let [left, filler, junction, right] = self.borders.horizontal(line, H)?;
+        let filler = filler.to_string().repeat(self.cell_columns() as usize);
+        let inner = vec![filler; W].join(&junction.to_string());
+        Some(format!("{}{}{}", left, inner, right))
+    }
+
+    /// The text in a square, padded out to the width of the square.
+    fn cell_text(&self, cell: Option<Square>) -> String {
+        let width = self.cell_columns() as usize;
+        let Some(cell) = cell else {
+            return format!("{:width$}", "");
+        };
+        if self.compact {
+            // Always on the right, so that there's a gap between neighbours
+            return format!("{:>width$x}", cell.0);
+        }
+        self.justified(&self.label(cell, width), width)
+    }
+
+    /// Each row of a square, padded out to the width of the square, which is a single row unless
+    /// numbers are drawn big.
+    fn cell_lines(&self, cell: Option<Square>) -> Vec<String> {
+        if !self.big {
+            return vec![self.cell_text(cell)];
+        }
+        let width = self.cell_columns() as usize;
+        let Some(cell) = cell else {
+            return vec![format!("{:width$}", ""); self.cell_height() as usize];
+        };
+        let label = self.label(cell, self.cell_width as usize - 1);
+        font::big_text(&label)
+            .iter()
+            .map(|line| format!(" {} ", self.justified(line, width - 2)))
+            .collect()
+    }
+
+    /// What's written on a tile, in at most `width` characters where it can be.
+    fn label(&self, cell: Square, width: usize) -> String {
+        if self.exponents {
+            cell.0.to_string()
         } else {
-            if self.cursor_row != 0 {
-                self.output
-                    .queue(crossterm::cursor::MoveUp(self.cursor_row))?;
-            }
-            for row in &board.rows {
-                self.output.queue(crossterm::cursor::MoveDown(1))?;
-                self.output.queue(crossterm::cursor::MoveToColumn(0))?;
-
-                let mut first_cell = true;
-                for cell in row {
-                    if first_cell {
-                        first_cell = false;
-                    } else {
-                    }
+            cell.label(width)
+        }
+    }
 
-                    if let Some(cell) = cell {
-                        self.draw_cell(*cell)?;
-                    } else {
-                        self.output
-                            .queue(crossterm::cursor::MoveRight(MAX_DIGIT_WIDTH))?;
-                    }
+    /// Pad `text` out to `width` characters, on whichever sides it's justified to.
+    fn justified(&self, text: &str, width: usize) -> String {
+        match self.justify {
+            Justify::Left => format!("{:<width$}", text),
+            Justify::Center => format!("{:^width$}", text),
+            Justify::Right => format!("{:>width$}", text),
+        }
+    }
+
+    /// The board as plain text, laid out just as it is on the screen but without any colours.
+    fn render(&self, board: &Board<W, H>) -> String {
+        let mut lines = Vec::new();
+        for (row_id, row) in board.rows.iter().enumerate() {
+            lines.extend(self.border_line(row_id));
+            let vertical = self
+                .borders
+                .vertical()
+                .map(String::from)
+                .unwrap_or_default();
+            for line_id in 0..self.cell_height() as usize {
+                let mut line = vertical.clone();
+                for &cell in row {
+                    line += &self.cell_lines(cell)[line_id];
+                    line += &vertical;
                 }
+                lines.push(line);
             }
-            self.cursor_row = SIZE - 1;
         }
+        lines.extend(self.border_line(H));
+        lines.join("\n")
+    }
+}
+
+/// The board as plain text, laid out exactly as the [Renderer] draws it by default but without
+/// any colours or escape codes, for checking what the player would see.
+pub fn render_to_string<const W: usize, const H: usize>(board: &Board<W, H>) -> String {
+    Layout::default().render(board)
+}
+
+impl Clock {
+    /// Whether the clock is counting.
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Start counting from `now`, unless the clock is already running.
+    pub fn start(&mut self, now: Instant) {
+        self.running_since.get_or_insert(now);
+    }
+
+    /// Stop counting at `now`, keeping the time counted so far.
+    pub fn stop(&mut self, now: Instant) {
+        if let Some(since) = self.running_since.take() {
+            self.banked += now.saturating_duration_since(since);
+        }
+    }
 
-        self.old_board = Some(*board);
-        self.output.flush()
+    /// How much time the clock has counted, as of `now`.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        let running = self
+            .running_since
+            .map_or(Duration::ZERO, |since| now.saturating_duration_since(since));
+        self.banked + running
     }
 }
 
-impl<Output: Write> Drop for Renderer<Output> {
-    fn drop(&mut self) {
-        self.output.flush().ok();
-        crossterm::terminal::disable_raw_mode().ok();
-        self.output.queue(crossterm::cursor::Show).ok();
-        writeln!(self.output).ok();
-        self.output.flush().ok();
+impl Pacer {
+    /// Create a pacer that holds each frame for at least `min_frame_time`.
+    pub fn new(min_frame_time: Duration) -> Self {
+        Pacer {
+            min_frame_time,
+            last_frame: None,
+        }
+    }
+
+    /// How much longer the current frame needs to stay on screen, as of `now`.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        match self.last_frame {
+            Some(last_frame) => self
+                .min_frame_time
+                .saturating_sub(now.saturating_duration_since(last_frame)),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Record that a frame went on the screen at `now`.
+    pub fn frame_shown(&mut self, now: Instant) {
+        self.last_frame = Some(now);
+    }
+
+    /// Block until the current frame has been shown for long enough.
+    pub fn wait(&self) {
+        let remaining = self.remaining(Instant::now());
+        if !remaining.is_zero() {
+            std::thread::sleep(remaining);
+        }
     }
 }
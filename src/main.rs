@@ -1,8 +1,1179 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use play_2048::{
+    daily::Date,
+    highscore::HighScore,
+    keys::{KeyBindings, KeyRepeat},
+    recording::{Action, Recording},
+    report::Report,
+    scoring::Combo,
+    snapshot::Snapshot,
+    solver, spawn_label,
+    spectate::{Mode, SpectateCode},
+    Animation, Board, Borders, ChaCha12Rng, CrosstermBackend, Game, Justify, Move, Pacer, Renderer,
+    Square, Stats, Theme, DEFAULT_SPAWNS, DEFAULT_STARTING_TILES,
+};
+use rand::SeedableRng;
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// The shortest time a board will be on screen before the next queued move is applied.
+const MIN_FRAME_TIME: Duration = Duration::from_millis(30);
+
+/// The shortest time between moves made by holding a key down, however fast the terminal repeats
+/// it.
+const REPEAT_INTERVAL: Duration = Duration::from_millis(80);
+
+/// How long autoplay waits between moves, so that a human can follow along.
+const AUTOPLAY_DELAY: Duration = Duration::from_millis(100);
+
+/// How long a replay waits between moves.
+const REPLAY_DELAY: Duration = Duration::from_millis(300);
+
+/// How long a hint stays on the screen.
+const HINT_DURATION: Duration = Duration::from_secs(1);
+
+/// How long a new milestone is announced for.
+const MILESTONE_DURATION: Duration = Duration::from_secs(2);
+
+/// The smallest tile worth announcing the first time it's made. Smaller ones come too often.
+const FIRST_MILESTONE: u32 = 256;
+
+/// How far the mouse has to be dragged for a swipe to count, in rows. Columns are about half as
+/// wide as rows are tall, so it takes twice as many of them.
+const SWIPE_THRESHOLD: u16 = 2;
+
+/// How tiles slide into place when animations are turned on.
+const ANIMATION: Animation = Animation {
+    frames: 3,
+    frame_time: Duration::from_millis(15),
+};
+
+/// What `--help` prints.
+const USAGE: &str = "\
+Usage: 2048 [options]
+       2048 spectate <code> [options]
+
+Playing:
+  --seed <number>          Start the random number generator from a seed
+  --daily                  Play the day's challenge, the same as everyone else today
+  --start <file>           Start from a board in a file instead of two random tiles
+  --start-tiles <count>    Start with 1 to 16 random tiles instead of two
+  --goal <board>           Win by matching a board, such as 2,4,.,./.,.,.,./...
+  --target <tile>          Win by making a tile other than 2048
+  --fours <percent>        How often a new tile is a 4, if not 10%
+  --no-spawn               Don't add a tile after each move
+  --combo                  Score each merge in a move for more than the one before
+  --moves <count>          Limit how many moves can be made
+  --time <seconds>         Limit how long the game lasts
+  --auto                   Start with the solver playing
+  --delay <milliseconds>   How long autoplay and replays wait between moves
+  --no-confirm             Quit on Esc or Ctrl-C without asking
+  --keys <file>            Read key bindings from a file
+
+Recording:
+  --record <file>          Write the game to a file as it's played
+  --replay <file>          Play back a recorded game
+  --script                 Read moves from stdin and print the board at the end
+  --dump-json              Read moves from stdin and write the game out as JSON
+
+Looks:
+  --theme <name>           classic, high-contrast or mono
+  --mono, --no-color       The same as --theme mono
+  --borders <style>        Draw borders, unicode or ascii
+  --cell-width <width>     3, 4 or 5 characters across each square
+  --justify <side>         left, center or right
+  --exponents              Show each tile's exponent instead of its number
+  --big                    Write numbers in big characters when there's room
+  --centre, --center       Draw the game in the middle of the screen
+  --animate                Show tiles sliding into place
+  --fade                   Fade merged tiles into their new colours, with --animate
+  --speed                  Show how many moves a second are being made
+  --bell                   Ring the bell on merges and flash the screen on losing
+  --park-cursor            Leave the cursor below the board
+  -h, --help               Show this and quit
+
+Keys:
+  Arrows, WASD, hjkl       Move
+  ?                        Show the solver's hint
+  Space                    Turn autoplay on or off
+  u, y                     Undo, redo
+  r                        Start again
+  e                        Explain the last move
+  n                        Step through a replay
+  F5, F9                   Save and load the game
+  F12                      Write a bug report
+  Esc, Ctrl-C              Quit
+";
+
+/// Settings chosen on the command line.
+#[derive(Default)]
+struct Options {
+    /// Print the usage and quit instead of playing
+    help: bool,
+    /// Start with the solver choosing the moves
+    auto: bool,
+    /// A game someone shared for the solver to play, which sets the seed and scoring
+    spectate: Option<SpectateCode>,
+    theme: Theme,
+    /// How many characters across each square is, if not the default
+    cell_width: Option<u16>,
+    /// Show tiles sliding into place
+    animate: bool,
+    /// Fade tiles made by a merge into their new colours, as part of the animation
+    fade: bool,
+    borders: Borders,
+    /// Where the number goes in each square
+    justify: Justify,
+    /// Show each tile's exponent instead of its number
+    exponents: bool,
+    /// Write numbers in big characters, when the window is big enough
+    big: bool,
+    /// Draw the game in the middle of the screen
+    centre: bool,
+    /// Leave the cursor below the board after each draw, for terminals that won't hide it
+    park_cursor: bool,
+    /// Where to start the random number generator, so that a game can be played again
+    seed: Option<u64>,
+    /// Play the day's challenge, with the same tiles as everyone else playing today
+    daily: bool,
+    /// Where to write a log of the game
+    record: Option<PathBuf>,
+    /// A log of a game to play back, instead of playing a new one
+    replay: Option<PathBuf>,
+    /// A board to start from instead of two random tiles
+    start: Option<PathBuf>,
+    /// How many random tiles to start with, if not the usual two
+    start_tiles: Option<usize>,
+    /// An arrangement of tiles that wins the game when the board matches it, for puzzles
+    goal: Option<Board>,
+    /// Don't add a tile after each move, for studying how moves work
+    no_spawn: bool,
+    /// How often a new tile is a 4 rather than a 2, as a percentage, if not one time in ten
+    fours: Option<f64>,
+    /// Play without the screen, taking moves from stdin and writing the game out as JSON
+    dump_json: bool,
+    /// Play without the screen, taking moves from stdin and printing the board at the end
+    script: bool,
+    /// The tile that wins the game, if not 2048
+    target: Option<Square>,
+    /// Score each merge in a move for more than the one before it
+    combo: bool,
+    /// Quit straight away on Esc or Ctrl-C, without asking first
+    no_confirm: bool,
+    /// A file of key bindings to use instead of the usual ones
+    keys: Option<PathBuf>,
+    /// Show how many moves a second are being made
+    speed: bool,
+    /// Ring the bell on merges and flash the screen on losing
+    bell: bool,
+    /// How many moves the player gets, if they're limited
+    moves: Option<u32>,
+    /// How long the player gets, if it's limited
+    time: Option<Duration>,
+    /// How long to wait between moves of autoplay and replays, if not the usual, which also paces
+    /// animations
+    delay: Option<Duration>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        // See https://no-color.org
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            options.theme = Theme::mono();
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--auto" => options.auto = true,
+                "spectate" => match args.next().map(|code| code.parse::<SpectateCode>()) {
+                    Some(Ok(code)) => options.spectate = Some(code),
+                    Some(Err(error)) => return Err(error.to_string()),
+                    None => return Err("spectate needs a code, such as classic-4x4-2a".to_string()),
+                },
+                "--animate" => options.animate = true,
+                "--fade" => options.fade = true,
+                "--centre" | "--center" => options.centre = true,
+                "--park-cursor" => options.park_cursor = true,
+                "--no-confirm" => options.no_confirm = true,
+                "--speed" => options.speed = true,
+                "--bell" => options.bell = true,
+                "--no-spawn" => options.no_spawn = true,
+                "--fours" => match args.next().and_then(|fours| fours.parse().ok()) {
+                    Some(percent @ 0.0..=100.0) => options.fours = Some(percent / 100.0),
+                    _ => return Err("--fours must be a percentage from 0 to 100".to_string()),
+                },
+                "--combo" => options.combo = true,
+                "--exponents" => options.exponents = true,
+                "--big" => options.big = true,
+                "--daily" => options.daily = true,
+                "--dump-json" => options.dump_json = true,
+                "--script" => options.script = true,
+                "--time" => match args.next().and_then(|time| time.parse().ok()) {
+                    Some(seconds) if seconds > 0 => {
+                        options.time = Some(Duration::from_secs(seconds))
+                    }
+                    _ => return Err("--time must be a positive number of seconds".to_string()),
+                },
+                "--target" => match args.next().and_then(|target| target.parse().ok()) {
+                    Some(target) => match Square::from_value(target) {
+                        Some(square) if target >= 4 => options.target = Some(square),
+                        _ => {
+                            return Err(format!(
+                                "--target must be a power of two from 4 up, not {}",
+                                target
+                            ))
+                        }
+                    },
+                    None => return Err("--target must be a whole number".to_string()),
+                },
+                "--delay" => match args.next().and_then(|delay| delay.parse().ok()) {
+                    Some(millis) => options.delay = Some(Duration::from_millis(millis)),
+                    None => {
+                        return Err("--delay must be a whole number of milliseconds".to_string())
+                    }
+                },
+                "--moves" => match args.next().and_then(|moves| moves.parse().ok()) {
+                    Some(moves) if moves > 0 => options.moves = Some(moves),
+                    _ => return Err("--moves must be a positive whole number".to_string()),
+                },
+                "--mono" | "--no-color" => options.theme = Theme::mono(),
+                "--borders" => {
+                    options.borders = match args.next().as_deref() {
+                        Some("unicode") => Borders::Unicode,
+                        Some("ascii") => Borders::Ascii,
+                        _ => return Err("--borders must be unicode or ascii".to_string()),
+                    }
+                }
+                "--justify" => {
+                    options.justify = match args.next().as_deref() {
+                        Some("left") => Justify::Left,
+                        Some("center" | "centre") => Justify::Center,
+                        Some("right") => Justify::Right,
+                        _ => return Err("--justify must be left, center or right".to_string()),
+                    }
+                }
+                "--seed" => match args.next().and_then(|seed| seed.parse().ok()) {
+                    Some(seed) => options.seed = Some(seed),
+                    None => return Err("--seed must be a whole number".to_string()),
+                },
+                "--record" => match args.next() {
+                    Some(path) => options.record = Some(path.into()),
+                    None => return Err("--record needs a file to write to".to_string()),
+                },
+                "--keys" => match args.next() {
+                    Some(path) => options.keys = Some(path.into()),
+                    None => return Err("--keys needs a file to read from".to_string()),
+                },
+                "--replay" => match args.next() {
+                    Some(path) => options.replay = Some(path.into()),
+                    None => return Err("--replay needs a file to read from".to_string()),
+                },
+                "--start" => match args.next() {
+                    Some(path) => options.start = Some(path.into()),
+                    None => return Err("--start needs a file to read from".to_string()),
+                },
+                "--goal" => match args.next().map(|goal| Board::from_compact(&goal)) {
+                    Some(Ok(goal)) => options.goal = Some(goal),
+                    Some(Err(error)) => return Err(format!("--goal isn't a board: {}", error)),
+                    None => {
+                        return Err("--goal needs a board, such as 2,4,.,./.,.,.,./...".to_string())
+                    }
+                },
+                "--start-tiles" => match args.next().and_then(|tiles| tiles.parse().ok()) {
+                    Some(tiles @ 1..=16) => options.start_tiles = Some(tiles),
+                    _ => return Err("--start-tiles must be between 1 and 16".to_string()),
+                },
+                "--cell-width" => {
+                    options.cell_width = match args.next().as_deref() {
+                        Some(width @ ("3" | "4" | "5")) => width.parse().ok(),
+                        _ => return Err("--cell-width must be 3, 4 or 5".to_string()),
+                    }
+                }
+                "--theme" => {
+                    options.theme = match args.next().as_deref() {
+                        Some("classic") => Theme::classic(),
+                        Some("high-contrast") => Theme::high_contrast(),
+                        Some("mono") => Theme::mono(),
+                        _ => {
+                            return Err("--theme must be classic, high-contrast or mono".to_string())
+                        }
+                    }
+                }
+                other => return Err(format!("unrecognised argument {:?}", other)),
+            }
+        }
+        if options.fade && !options.animate {
+            return Err("--fade only works with --animate".to_string());
+        }
+        if options.replay.is_some() && (options.record.is_some() || options.seed.is_some()) {
+            return Err("--replay can't be used with --record or --seed".to_string());
+        }
+        if options.daily
+            && (options.seed.is_some() || options.replay.is_some() || options.start.is_some())
+        {
+            return Err("--daily can't be used with --seed, --replay or --start".to_string());
+        }
+        // Recordings only know the seed, so they'd play back from random tiles
+        if options.start.is_some() && (options.replay.is_some() || options.record.is_some()) {
+            return Err("--start can't be used with --replay or --record".to_string());
+        }
+        if options.start_tiles.is_some()
+            && (options.start.is_some() || options.replay.is_some() || options.record.is_some())
+        {
+            return Err(
+                "--start-tiles can't be used with --start, --replay or --record".to_string(),
+            );
+        }
+        // Recordings don't know how often 4s came up, so they'd play back with other tiles
+        if options.fours.is_some() && (options.replay.is_some() || options.record.is_some()) {
+            return Err("--fours can't be used with --replay or --record".to_string());
+        }
+        if options.no_spawn && (options.replay.is_some() || options.record.is_some()) {
+            return Err("--no-spawn can't be used with --replay or --record".to_string());
+        }
+        if options.dump_json && (options.replay.is_some() || options.record.is_some()) {
+            return Err("--dump-json can't be used with --replay or --record".to_string());
+        }
+        if options.script
+            && (options.dump_json || options.replay.is_some() || options.record.is_some())
+        {
+            return Err(
+                "--script can't be used with --dump-json, --replay or --record".to_string(),
+            );
+        }
+        if let Some(code) = options.spectate {
+            // Anything else that changes the tiles would make it a different game to the shared one
+            if options.seed.is_some()
+                || options.daily
+                || options.replay.is_some()
+                || options.start.is_some()
+                || options.start_tiles.is_some()
+                || options.fours.is_some()
+                || options.no_spawn
+                || options.combo
+            {
+                return Err("spectate only takes options that don't change the game".to_string());
+            }
+            options.seed = Some(code.seed);
+            options.combo = code.mode == Mode::Combo;
+            options.auto = true;
+        }
+        Ok(options)
+    }
+}
+
+/// Where games are saved by default, `~/.2048_save`, if there's a home directory.
+fn default_save_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(std::path::Path::new(&home).join(".2048_save"))
+}
+
+/// Whether the game loop should keep going after handling an event.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Stop,
+}
+
+/// Everything the game loop keeps track of between events.
+struct App<Output: Write> {
+    game: Game<ChaCha12Rng>,
+    renderer: Renderer<CrosstermBackend<Output>>,
+    pacer: Pacer,
+    /// Which keys play which moves
+    keys: KeyBindings,
+    /// Whether to ring the bell on merges and flash the screen on losing
+    bell: bool,
+    /// How long to wait between moves the player isn't making, if it's been chosen rather than
+    /// left to depend on whether it's a replay
+    delay: Option<Duration>,
+    /// How many moves the player gets, for a challenge to score as much as possible with them
+    move_limit: Option<u32>,
+    /// The board the game started from, and starts from again on a restart, if it wasn't random
+    start: Option<Board>,
+    /// How many random tiles each new game starts with, when it doesn't have a fixed start
+    starting_tiles: usize,
+    /// The arrangement that wins a puzzle, if this is one
+    goal: Option<Board>,
+    /// The seed of the day's challenge, which every new game uses, if that's what's being played
+    daily_seed: Option<u64>,
+    /// What the game being played was seeded with, unless it was loaded from a save
+    seed: Option<u64>,
+    /// The board before the most recent move, along with that move, so that it can be explained
+    last_move: Option<(Board, Move)>,
+    /// When to take down the hint or milestone that's on the screen, if there is one
+    hint_until: Option<Instant>,
+    /// The win banner is only shown once, and stays up until the player presses a key
+    won: bool,
+    celebrating: bool,
+    /// The biggest tile made so far this session, across every game, so that each milestone is
+    /// only announced once
+    best_tile: Option<Square>,
+    /// Whether the game has ended, leaving the final board up until the player starts again or
+    /// quits
+    over: bool,
+    /// Whether merges are scored as combos, each worth more than the one before it in a move
+    combo: bool,
+    /// Whether to ask before quitting a game in progress
+    confirm_quit: bool,
+    /// Whether the player has asked to quit once, and is being asked to do it again to be sure
+    quitting: bool,
+    /// Slows down held keys
+    key_repeat: KeyRepeat,
+    /// Where the mouse button went down, as a column and row, if a swipe has started
+    swipe_from: Option<(u16, u16)>,
+    /// Whether the solver is choosing the moves
+    autoplay: bool,
+    /// When the solver should make its next move
+    autoplay_at: Instant,
+    high_score: HighScore,
+    /// Where to keep the high score between sessions, if anywhere
+    high_score_path: Option<PathBuf>,
+    /// Where F5 saves the game and F9 loads it back from, if anywhere
+    save_path: Option<PathBuf>,
+    /// The log being kept of this game, and where to save it, if it's being recorded
+    recording: Option<(PathBuf, Recording)>,
+    /// What's left of the game being played back, if this is a replay
+    replay: Option<std::vec::IntoIter<Action>>,
+}
+
+impl<Output: Write> App<Output> {
+    /// The numbers to show above the board.
+    fn stats(&self) -> Stats {
+        let score = self.game.score();
+        let no_spawns = !self.game.auto_spawn();
+        // Only whole seconds are shown, so the header needn't change any more often than that
+        let elapsed = Duration::from_secs(self.game.clock().elapsed(Instant::now()).as_secs());
+        Stats {
+            score,
+            board_sum: self.game.board().board_sum(),
+            // Scores made without new tiles don't count
+            best: if no_spawns {
+                self.high_score.score
+            } else {
+                self.high_score.score.max(score)
+            },
+            moves: self.game.moves_made(),
+            moves_left: self
+                .move_limit
+                .map(|limit| limit.saturating_sub(self.game.moves_made())),
+            elapsed: Some(elapsed),
+            time_left: self
+                .game
+                .time_limit()
+                .map(|limit| limit.saturating_sub(elapsed)),
+            no_spawns,
+            combo: self.combo.then(|| self.game.last_multiplier()),
+            spawns: (self.game.spawns() != DEFAULT_SPAWNS).then(|| spawn_label(self.game.spawns())),
+        }
+    }
+
+    fn draw(&mut self) -> crossterm::Result<()> {
+        let stats = self.stats();
+        self.renderer.draw_board(self.game.board(), &stats)
+    }
+
+    /// Draw everything again from scratch, rather than only what's changed.
+    fn redraw(&mut self) -> crossterm::Result<()> {
+        let stats = self.stats();
+        self.renderer.force_redraw(self.game.board(), &stats)
+    }
+
+    /// Take down any message, such as a hint or an explanation.
+    fn clear_message(&mut self) -> crossterm::Result<()> {
+        self.hint_until = None;
+        self.renderer.message("")
+    }
+
+    /// How long to wait between moves that the player isn't making.
+    fn step_delay(&self) -> Duration {
+        match self.delay {
+            Some(delay) => delay,
+            None if self.replay.is_some() => REPLAY_DELAY,
+            None => AUTOPLAY_DELAY,
+        }
+    }
+
+    /// Keep track of something the player did, if the game is being recorded.
+    fn record(&mut self, action: Action) {
+        if let Some((_, recording)) = &mut self.recording {
+            recording.actions.push(action);
+        }
+    }
+
+    /// Whether the game has come to an end, either with no moves left on the board or none left
+    /// to play.
+    fn is_finished(&self) -> bool {
+        self.game.board().is_game_over() || self.out_of_moves() || self.out_of_time()
+    }
+
+    /// Whether every move allowed has been played.
+    fn out_of_moves(&self) -> bool {
+        self.move_limit
+            .is_some_and(|limit| self.game.moves_made() >= limit)
+    }
+
+    /// Whether the time allowed has all been used up.
+    fn out_of_time(&self) -> bool {
+        self.game.out_of_time(Instant::now())
+    }
+
+    /// Finish the game, recording a new high score if there is one.
+    fn game_over(&mut self) -> crossterm::Result<Flow> {
+        self.game.clock_mut().stop(Instant::now());
+        let max_tile = self.game.board().max_tile().map_or(0, Square::value);
+        // A replay is somebody else's game, already counted when it was played, and a game without
+        // new tiles isn't a real one
+        let counts = self.replay.is_none() && self.game.auto_spawn();
+        if counts && self.high_score.update(self.game.score(), max_tile) {
+            if let Some(path) = &self.high_score_path {
+                // If it can't be saved, it's still remembered for the rest of this session
+                self.high_score.save(path).ok();
+            }
+        }
+        let solved = self
+            .goal
+            .as_ref()
+            .is_some_and(|goal| self.game.board().is_solved_against(goal));
+        let challenge_over = if solved {
+            Some("Solved! You win!")
+        } else if self.game.board().is_game_over() {
+            None
+        } else if self.out_of_time() {
+            Some("Time!")
+        } else if self.out_of_moves() {
+            Some("Out of moves!")
+        } else {
+            None
+        };
+        let stats = self.stats();
+        self.renderer
+            .end_screen(challenge_over.unwrap_or("Game over"), &stats, max_tile)?;
+        if self.bell {
+            self.renderer.flash()?;
+        }
+        // Nobody is watching for a key to start again when the moves play themselves
+        if self.autoplay {
+            return Ok(Flow::Stop);
+        }
+        self.over = true;
+        Ok(Flow::Continue)
+    }
+
+    /// Throw away the game in progress, or the one that just ended, and start a new one. The high
+    /// score carries on, and a recording starts again from the new game. A game with a fixed start
+    /// begins from it again, as does the day's challenge.
+    fn restart(&mut self) -> crossterm::Result<()> {
+        let seed = self.daily_seed.unwrap_or_else(rand::random);
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        let game = match self.start {
+            Some(board) => Game::from_board(rng, board),
+            None => Game::with_starting_tiles(rng, self.starting_tiles),
+        };
+        self.replace_game(game)?;
+        self.seed = Some(seed);
+        if let Some((_, recording)) = &mut self.recording {
+            *recording = Recording::new(seed);
+        }
+        // The seed printed at the start is for the old game, so show the new one
+        self.renderer.message(&format!("New game, seed {}", seed))?;
+        Ok(())
+    }
+
+    /// Carry on with `game` in place of the one being played, with the same settings as the old
+    /// one.
+    fn replace_game(&mut self, game: Game<ChaCha12Rng>) -> crossterm::Result<()> {
+        let auto_spawn = self.game.auto_spawn();
+        let target = self.game.target();
+        let spawns = self.game.spawns().to_vec();
+        let time_limit = self.game.time_limit();
+        self.game = game;
+        self.game.set_time_limit(time_limit);
+        self.game.set_auto_spawn(auto_spawn);
+        self.game.set_spawns(&spawns);
+        self.game.set_target(target);
+        if self.combo {
+            self.game.set_scoring_rule(Box::new(Combo));
+        }
+        self.game.clock_mut().start(Instant::now());
+        self.last_move = None;
+        // A loaded game that's already been won doesn't get the banner again
+        self.won = self.game.board().has_won(target);
+        self.celebrating = false;
+        self.over = false;
+        self.hint_until = None;
+        self.redraw()
+    }
+
+    /// Save the game so that it can be carried on with later, even after quitting.
+    fn save_game(&mut self) -> crossterm::Result<()> {
+        let Some(path) = &self.save_path else {
+            return self.renderer.message("Nowhere to save the game");
+        };
+        let message = match self.game.save(path) {
+            Ok(()) => format!("Saved to {}", path.display()),
+            Err(error) => format!("Couldn't save the game: {}", error),
+        };
+        self.hint_until = None;
+        self.renderer.message(&message)
+    }
+
+    /// Write everything about the game that could help with a bug report to a new file in the
+    /// current directory, and say where it went. This works just as well once the game is over.
+    fn write_report(&mut self) -> crossterm::Result<()> {
+        let report = Report {
+            seed: self.seed,
+            mode: self.mode(),
+            board: *self.game.board(),
+            moves: self.game.history().to_vec(),
+            terminal_size: crossterm::terminal::size().ok(),
+            time: SystemTime::now(),
+        };
+        let message = match report.write_in(Path::new(".")) {
+            Ok(path) => format!("Wrote a report to {}", path.display()),
+            Err(error) => format!("Couldn't write a report: {}", error),
+        };
+        self.hint_until = None;
+        self.renderer.message(&message)
+    }
+
+    /// What's different about the game being played from a classic one, such as `daily, combo`,
+    /// or `classic` if nothing is.
+    fn mode(&self) -> String {
+        let mut mode = Vec::new();
+        if self.daily_seed.is_some() {
+            mode.push("daily".to_string());
+        }
+        if self.replay.is_some() {
+            mode.push("replay".to_string());
+        }
+        if self.goal.is_some() {
+            mode.push("puzzle".to_string());
+        } else if self.start.is_some() {
+            mode.push("fixed start".to_string());
+        }
+        if self.combo {
+            mode.push("combo".to_string());
+        }
+        if !self.game.auto_spawn() {
+            mode.push("no spawns".to_string());
+        }
+        if self.game.spawns() != DEFAULT_SPAWNS {
+            mode.push(spawn_label(self.game.spawns()));
+        }
+        if self.game.target() != Square::WIN {
+            mode.push(format!("target {}", self.game.target().value()));
+        }
+        if let Some(moves) = self.move_limit {
+            mode.push(format!("{} moves", moves));
+        }
+        if let Some(time) = self.game.time_limit() {
+            mode.push(format!("{}s", time.as_secs()));
+        }
+        if mode.is_empty() {
+            "classic".to_string()
+        } else {
+            mode.join(", ")
+        }
+    }
+
+    /// Go back to the saved game, throwing away the one being played.
+    fn load_game(&mut self) -> crossterm::Result<()> {
+        self.hint_until = None;
+        // The recording only knows the seed it started from, so couldn't carry on from the save
+        if self.recording.is_some() {
+            return self.renderer.message("Can't load a game while recording");
+        }
+        let Some(path) = &self.save_path else {
+            return self.renderer.message("Nowhere to load a game from");
+        };
+        match Game::load(path) {
+            Ok(game) => {
+                let message = format!("Loaded {}", path.display());
+                self.replace_game(game)?;
+                self.seed = None;
+                self.renderer.message(&message)
+            }
+            Err(error) => self
+                .renderer
+                .message(&format!("Couldn't load the game: {}", error)),
+        }
+    }
+
+    /// Take down the win banner.
+    fn dismiss_win(&mut self) -> crossterm::Result<Flow> {
+        self.celebrating = false;
+        self.renderer.message("")?;
+        // The winning move might also have been the last one possible
+        if self.is_finished() {
+            return self.game_over();
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Play a move, whether it came from the player or the solver.
+    fn play(&mut self, action: Move) -> crossterm::Result<Flow> {
+        // A move that comes in as the time runs out, before the game has noticed, is finished all
+        // the same, and the game ends straight after it
+        let before = *self.game.board();
+        self.pacer.wait();
+        let result = self.game.step(action);
+        if !result.moved {
+            return Ok(Flow::Continue);
+        }
+        self.record(Action::Move(action));
+        self.clear_message()?;
+        self.last_move = Some((before, action));
+        if self.bell && result.merged_tiles > 0 {
+            self.renderer.bell()?;
+        }
+        let stats = self.stats();
+        self.renderer
+            .draw_board_animated(self.game.board(), &stats, action, result.new_tile)?;
+        self.pacer.frame_shown(Instant::now());
+
+        // A puzzle is over once it's solved, since there's nothing left to arrange
+        if let Some(goal) = &self.goal {
+            if self.game.board().is_solved_against(goal) {
+                self.won = true;
+                return self.game_over();
+            }
+        }
+
+        let target = self.game.target();
+        if !self.won && self.game.board().has_won(target) {
+            self.won = true;
+            self.celebrating = true;
+            self.best_tile = self.best_tile.max(Some(target));
+            self.renderer.message(&format!(
+                "You made {}! You win! Press any key to keep playing",
+                target.value()
+            ))?;
+            return Ok(Flow::Continue);
+        }
+        self.check_milestone()?;
+
+        if result.game_over || self.out_of_moves() || self.out_of_time() {
+            self.celebrating = false;
+            return self.game_over();
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Announce the biggest tile on the board if it's the first of its kind this session. The win
+    /// has a banner of its own, so it isn't announced again here.
+    fn check_milestone(&mut self) -> crossterm::Result<()> {
+        let max_tile = self.game.board().max_tile();
+        if max_tile <= self.best_tile {
+            return Ok(());
+        }
+        self.best_tile = max_tile;
+        if let Some(tile) = max_tile.filter(|tile| tile.value() >= FIRST_MILESTONE) {
+            self.renderer
+                .message(&format!("New milestone: {}!", tile.value()))?;
+            self.hint_until = Some(Instant::now() + MILESTONE_DURATION);
+        }
+        Ok(())
+    }
+
+    /// Take back the most recent move, if there is one.
+    fn undo(&mut self) -> crossterm::Result<()> {
+        // Once time is up there's no going back
+        if self.out_of_time() {
+            return Ok(());
+        }
+        if self.game.undo() {
+            // Taking back the last move of a lost game puts it back in play
+            self.over = false;
+            self.game.clock_mut().start(Instant::now());
+            self.record(Action::Undo);
+            self.clear_message()?;
+            self.last_move = None;
+            self.draw()?;
+        }
+        Ok(())
+    }
+
+    /// Make the most recently undone move again, ending the game if that was the move that lost
+    /// it.
+    fn redo(&mut self) -> crossterm::Result<Flow> {
+        if self.out_of_time() || !self.game.redo() {
+            return Ok(Flow::Continue);
+        }
+        self.record(Action::Redo);
+        self.clear_message()?;
+        self.last_move = None;
+        self.draw()?;
+        if self.is_finished() {
+            return self.game_over();
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Quit, unless there's a game that would be lost by it, in which case wait until the player
+    /// asks a second time.
+    fn exit_request(&mut self) -> crossterm::Result<Flow> {
+        let in_progress = !self.over && self.replay.is_none() && self.game.moves_made() > 0;
+        if self.quitting || !self.confirm_quit || !in_progress {
+            return Ok(Flow::Stop);
+        }
+        self.quitting = true;
+        self.hint_until = None;
+        self.renderer
+            .message("Press again to quit, any other key to continue")?;
+        Ok(Flow::Continue)
+    }
+
+    /// Deal with anything waiting on the player's next input, such as the win banner or the
+    /// question about quitting. Returns what to do next if the input was used up by it.
+    fn answer_prompt(&mut self) -> crossterm::Result<Option<Flow>> {
+        if self.quitting {
+            self.quitting = false;
+            self.clear_message()?;
+            return Ok(Some(Flow::Continue));
+        }
+        if self.celebrating {
+            return self.dismiss_win().map(Some);
+        }
+        Ok(None)
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> crossterm::Result<Flow> {
+        // A report is wanted most when something is stuck, so a prompt doesn't get in its way
+        if code == KeyCode::F(12) {
+            self.write_report()?;
+            return Ok(Flow::Continue);
+        }
+        if let Some(flow) = self.answer_prompt()? {
+            return Ok(flow);
+        }
+
+        let action = match code {
+            KeyCode::Char(' ') => {
+                self.autoplay = !self.autoplay;
+                self.autoplay_at = Instant::now() + self.step_delay();
+                return Ok(Flow::Continue);
+            }
+            KeyCode::Char('e') => {
+                if let Some((before, action)) = self.last_move {
+                    self.renderer
+                        .message(&before.explain_move(action).join("; "))?;
+                    self.hint_until = None;
+                }
+                return Ok(Flow::Continue);
+            }
+            KeyCode::Char('n') if self.replay.is_some() => return self.replay_step(),
+            // Only the replay gets to change the board while it's playing back
+            _ if self.replay.is_some() => return Ok(Flow::Continue),
+            KeyCode::Char('u') => {
+                self.undo()?;
+                return Ok(Flow::Continue);
+            }
+            KeyCode::Char('y') => return self.redo(),
+            KeyCode::Char('r') => {
+                self.restart()?;
+                return Ok(Flow::Continue);
+            }
+            KeyCode::F(5) => {
+                self.save_game()?;
+                return Ok(Flow::Continue);
+            }
+            KeyCode::F(9) => {
+                self.load_game()?;
+                return Ok(Flow::Continue);
+            }
+            // All that's left to do after losing is to start again, undo or quit
+            _ if self.over => return Ok(Flow::Continue),
+            KeyCode::Char('?') => {
+                // Only worked out on request, as the search is too slow to run after every move
+                if let Some(hint) = solver::best_move(self.game.board()) {
+                    let arrow = match hint {
+                        Move::Up => '↑',
+                        Move::Down => '↓',
+                        Move::Left => '←',
+                        Move::Right => '→',
+                    };
+                    self.renderer
+                        .message(&format!("Hint: {} {:?}", arrow, hint))?;
+                    self.hint_until = Some(Instant::now() + HINT_DURATION);
+                }
+                return Ok(Flow::Continue);
+            }
+            other => match self.keys.get(other) {
+                Some(action) => action,
+                None => return Ok(Flow::Continue),
+            },
+        };
+        self.play(action)
+    }
+
+    /// Follow a swipe across the terminal, playing a move in the direction it mostly went once the
+    /// button is let go. Swipes that are too short are ignored.
+    fn handle_mouse(&mut self, event: MouseEvent) -> crossterm::Result<Flow> {
+        let position = (event.column, event.row);
+        let from = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.swipe_from = Some(position);
+                return Ok(Flow::Continue);
+            }
+            MouseEventKind::Up(MouseButton::Left) => match self.swipe_from.take() {
+                Some(from) => from,
+                None => return Ok(Flow::Continue),
+            },
+            _ => return Ok(Flow::Continue),
+        };
+
+        // Counted in rows, so that the same length of swipe counts the same either way
+        let across = (i32::from(position.0) - i32::from(from.0)) / 2;
+        let down = i32::from(position.1) - i32::from(from.1);
+        let threshold = i32::from(SWIPE_THRESHOLD);
+        let action = if across.abs() < threshold && down.abs() < threshold {
+            return Ok(Flow::Continue);
+        } else if across.abs() >= down.abs() {
+            if across > 0 {
+                Move::Right
+            } else {
+                Move::Left
+            }
+        } else if down > 0 {
+            Move::Down
+        } else {
+            Move::Up
+        };
+
+        if let Some(flow) = self.answer_prompt()? {
+            return Ok(flow);
+        }
+        if self.replay.is_some() || self.over {
+            return Ok(Flow::Continue);
+        }
+        self.play(action)
+    }
+
+    /// Respond to something from the terminal.
+    fn handle_event(&mut self, event: Event) -> crossterm::Result<Flow> {
+        match event {
+            Event::Key(evt) if is_exit_request(evt) => self.exit_request(),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Like a shell, for when something else has written over the game
+                self.redraw()?;
+                Ok(Flow::Continue)
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Like an editor, rather than restarting as a plain r does
+                self.handle_key(KeyCode::Char('y'))
+            }
+            Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.key_repeat.press(Instant::now());
+                self.handle_key(code)
+            }
+            // Holding a key down only repeats moves, and no faster than the interval allows
+            Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Repeat,
+                ..
+            }) if self.keys.get(code).is_some() => {
+                if !self.key_repeat.repeat(Instant::now()) {
+                    return Ok(Flow::Continue);
+                }
+                self.handle_key(code)
+            }
+            Event::Mouse(event) => self.handle_mouse(event),
+            Event::Resize(columns, rows) => {
+                self.renderer.resize((columns, rows))?;
+                self.draw()?;
+                Ok(Flow::Continue)
+            }
+            _ => Ok(Flow::Continue),
+        }
+    }
+
+    /// The next time something needs to happen without waiting for input, if ever.
+    fn deadline(&self) -> Option<Instant> {
+        let autoplay_at = (self.autoplay && !self.quitting).then_some(self.autoplay_at);
+        // The time in the header goes up each second while the clock is running
+        let clock = self.game.clock();
+        let next_second = clock.is_running().then(|| {
+            let now = Instant::now();
+            let into_second = clock.elapsed(now).subsec_nanos();
+            now + Duration::from_secs(1) - Duration::from_nanos(into_second.into())
+        });
+        [autoplay_at, self.hint_until, next_second]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// Do whatever is due to happen by now.
+    fn tick(&mut self, now: Instant) -> crossterm::Result<Flow> {
+        if self.hint_until.is_some_and(|until| until <= now) {
+            self.clear_message()?;
+        }
+        if self.game.clock().is_running() {
+            self.draw()?;
+            if self.out_of_time() {
+                self.celebrating = false;
+                return self.game_over();
+            }
+        }
+
+        // Hold off while asking about quitting, so that the question stays up
+        if self.autoplay && self.autoplay_at <= now && !self.quitting {
+            self.autoplay_at = now + self.step_delay();
+            return self.autoplay_step();
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Let the solver make a move, or play the next move of the replay.
+    fn autoplay_step(&mut self) -> crossterm::Result<Flow> {
+        if self.celebrating {
+            return self.dismiss_win();
+        }
+        if self.replay.is_some() {
+            return self.replay_step();
+        }
+
+        match solver::best_move(self.game.board()) {
+            Some(action) => self.play(action),
+            None => Ok(Flow::Stop),
+        }
+    }
+
+    /// Play the next thing the player did in the game being replayed.
+    fn replay_step(&mut self) -> crossterm::Result<Flow> {
+        match self.replay.as_mut().and_then(Iterator::next) {
+            Some(Action::Move(action)) => self.play(action),
+            Some(Action::Undo) => {
+                self.undo()?;
+                Ok(Flow::Continue)
+            }
+            Some(Action::Redo) => self.redo(),
+            None => {
+                self.autoplay = false;
+                self.renderer.message("End of the replay")?;
+                Ok(Flow::Continue)
+            }
+        }
+    }
+}
+
+/// Wait for the next thing to come in from the terminal, giving up at `deadline` if there is one.
+fn next_event(deadline: Option<Instant>) -> crossterm::Result<Option<Event>> {
+    match deadline {
+        Some(deadline) => {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if crossterm::event::poll(timeout)? {
+                crossterm::event::read().map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+        None => crossterm::event::read().map(Some),
+    }
+}
+
+/// Play a game with no screen, for other programs to drive. Moves are read from stdin one to a
+/// line, as `U`, `D`, `L` or `R` like in a recording, or `undo` or `redo`. A snapshot of the game
+/// is written to stdout at the start and after each line, until the game is over or stdin runs
+/// out.
+fn dump_json(mut game: Game<ChaCha12Rng>) -> std::io::Result<()> {
+    let stdout = std::io::stdout();
+    let mut output = stdout.lock();
+    writeln!(output, "{}", Snapshot::of(&game))?;
+    output.flush()?;
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let action = match line.trim() {
+            "undo" => Action::Undo,
+            "redo" => Action::Redo,
+            "" => continue,
+            other => match other.parse() {
+                Ok(direction) => Action::Move(direction),
+                Err(error) => {
+                    eprintln!("2048: {}", error);
+                    continue;
+                }
+            },
+        };
+        match action {
+            Action::Move(direction) => {
+                game.step(direction);
+            }
+            Action::Undo => {
+                game.undo();
+            }
+            Action::Redo => {
+                game.redo();
+            }
+        }
+        let snapshot = Snapshot::of(&game);
+        // Flushed every time, as whatever's reading is waiting on it before sending the next move
+        writeln!(output, "{}", snapshot)?;
+        output.flush()?;
+        if snapshot.game_over {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Play a game with no screen, for scripts and tests. Every letter on stdin is a move, `u`, `d`, `l`
+/// or `r` in either case, so they can come one to a line or all together. Anything else is reported
+/// and skipped. Once stdin runs out the board and score are printed.
+fn script(mut game: Game<ChaCha12Rng>) -> std::io::Result<()> {
+    for (line_number, line) in std::io::stdin().lock().lines().enumerate() {
+        for (column, letter) in line?.chars().enumerate() {
+            if letter.is_whitespace() {
+                continue;
+            }
+            let Ok(direction) = Move::try_from(letter) else {
+                eprintln!(
+                    "2048: {:?} on line {}, column {} is not a move",
+                    letter,
+                    line_number + 1,
+                    column + 1
+                );
+                continue;
+            };
+            game.step(direction);
+        }
+    }
+
+    println!("{}", game.board());
+    println!("Score: {}, moves: {}", game.score(), game.moves_made());
+    Ok(())
+}
 
 /// Figure out if the user is trying to escape the game, as raw mode stops all the usual suspects
 /// from working
 fn is_exit_request(key_event: KeyEvent) -> bool {
+    // A held key shouldn't quit, and then quit again at the prompt
+    if key_event.kind != KeyEventKind::Press {
+        return false;
+    }
+
     if key_event.code == KeyCode::Esc {
         return true;
     }
@@ -17,62 +1188,235 @@ fn is_exit_request(key_event: KeyEvent) -> bool {
 }
 
 fn main() -> crossterm::Result<()> {
-    let stdout = std::io::stdout();
-    let mut rng = rand::thread_rng();
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("2048: {}", error);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        print!("{}", USAGE);
+        return Ok(());
+    }
 
-    let mut board = play_2048::Board::new();
-    board.add_square(&mut rng);
-    board.add_square(&mut rng);
+    let high_score_path = HighScore::default_path();
+    let high_score = high_score_path
+        .as_deref()
+        .map_or_else(HighScore::default, HighScore::load);
 
-    let mut renderer = play_2048::Renderer::new(stdout.lock())?;
-    renderer.draw_board(&board)?;
+    let keys = match &options.keys {
+        Some(path) => match KeyBindings::load(path) {
+            Ok(keys) => keys,
+            Err(error) => {
+                eprintln!("2048: couldn't read {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        },
+        None => KeyBindings::default(),
+    };
 
-    loop {
-        match crossterm::event::read()? {
-            Event::Key(evt) if is_exit_request(evt) => {
-                break;
+    let replay = match &options.replay {
+        Some(path) => match Recording::load(path) {
+            Ok(recording) => Some(recording),
+            Err(error) => {
+                eprintln!("2048: couldn't read {}: {}", path.display(), error);
+                std::process::exit(1);
             }
-            Event::Key(KeyEvent {
-                code,
-                kind: KeyEventKind::Press,
-                ..
-            }) => {
-                let action = match code {
-                    KeyCode::Up | KeyCode::Char('w') => play_2048::Move::Up,
-                    KeyCode::Down | KeyCode::Char('s') => play_2048::Move::Down,
-                    KeyCode::Left | KeyCode::Char('a') => play_2048::Move::Left,
-                    KeyCode::Right | KeyCode::Char('d') => play_2048::Move::Right,
-                    _ => continue,
-                };
-                let new_board = board.apply_move(action);
-                if new_board == board {
-                    continue;
-                }
-                board = new_board;
-                board.add_square(&mut rng);
-                renderer.draw_board(&board)?;
-
-                let alive = [
-                    play_2048::Move::Up,
-                    play_2048::Move::Down,
-                    play_2048::Move::Left,
-                    play_2048::Move::Right,
-                ]
-                .iter()
-                .any(|&direction| board.apply_move(direction) != board);
-
-                if !alive {
-                    renderer.lose()?;
-                    break;
-                }
+        },
+        None => None,
+    };
+
+    let start = match &options.start {
+        Some(path) => match std::fs::read_to_string(path).map(|text| text.parse::<Board>()) {
+            Ok(Ok(board)) => Some(board),
+            Ok(Err(error)) => {
+                eprintln!(
+                    "2048: couldn't read a board from {}: {}",
+                    path.display(),
+                    error
+                );
+                std::process::exit(1);
             }
-            Event::Resize(columns, rows) => {
-                renderer.resize((columns, rows))?;
-                renderer.draw_board(&board)?;
+            Err(error) => {
+                eprintln!("2048: couldn't read {}: {}", path.display(), error);
+                std::process::exit(1);
             }
-            _ => {}
+        },
+        None => None,
+    };
+
+    let daily = options.daily.then(Date::today);
+    let seed = match (&replay, daily) {
+        (Some(recording), _) => recording.seed,
+        (None, Some(date)) => date.seed(),
+        (None, None) => options.seed.unwrap_or_else(rand::random),
+    };
+    let starting_tiles = options.start_tiles.unwrap_or(DEFAULT_STARTING_TILES);
+    if options.dump_json || options.script {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        let mut game = match start {
+            Some(board) => Game::from_board(rng, board),
+            None => Game::with_starting_tiles(rng, starting_tiles),
         };
+        game.set_auto_spawn(!options.no_spawn);
+        if let Some(fours) = options.fours {
+            game.set_four_probability(fours);
+        }
+        game.set_target(options.target.unwrap_or(Square::WIN));
+        if options.combo {
+            game.set_scoring_rule(Box::new(Combo));
+        }
+        return if options.script {
+            script(game)
+        } else {
+            dump_json(game)
+        };
+    }
+    // Printed before the game takes over the screen, so that it stays in the scrollback
+    match daily {
+        Some(date) => println!("Daily challenge for {}, seed: {}", date, seed),
+        None => println!("Seed: {}", seed),
+    }
+    // Only a game that the seed and scoring are enough to deal again can be shared
+    if options.start.is_none()
+        && options.start_tiles.is_none()
+        && options.fours.is_none()
+        && !options.no_spawn
+    {
+        let code = SpectateCode {
+            seed,
+            mode: if options.combo {
+                Mode::Combo
+            } else {
+                Mode::Classic
+            },
+            width: 4,
+            height: 4,
+        };
+        println!("Watch the solver play it with: 2048 spectate {}", code);
+    }
+
+    let stdout = std::io::stdout();
+    let mut renderer = Renderer::with_theme(stdout.lock(), options.theme)?;
+    if let Some(cell_width) = options.cell_width {
+        renderer.set_cell_width(cell_width)?;
+    }
+    renderer.set_borders(options.borders)?;
+    renderer.set_justify(options.justify);
+    renderer.set_exponents(options.exponents);
+    renderer.set_big(options.big)?;
+    renderer.set_centred(options.centre)?;
+    renderer.set_park_cursor(options.park_cursor);
+    if options.animate {
+        // The slide fits in the time between moves, so that it never holds the next one up
+        let frame_time = options.delay.map_or(ANIMATION.frame_time, |delay| {
+            delay.min(ANIMATION.frame_time * (ANIMATION.frames + 1)) / (ANIMATION.frames + 1)
+        });
+        renderer.set_animation(Some(Animation {
+            frame_time,
+            ..ANIMATION
+        }));
+        renderer.set_fade(options.fade);
+    }
+    renderer.set_show_rate(options.speed);
+    let rng = ChaCha12Rng::seed_from_u64(seed);
+    let mut app = App {
+        game: match start {
+            Some(board) => Game::from_board(rng, board),
+            None => Game::with_starting_tiles(rng, starting_tiles),
+        },
+        renderer,
+        // A delay shorter than usual is for watching at speed, so boards needn't stay up as long
+        pacer: Pacer::new(
+            options
+                .delay
+                .map_or(MIN_FRAME_TIME, |delay| delay.min(MIN_FRAME_TIME)),
+        ),
+        keys,
+        bell: options.bell,
+        delay: options.delay,
+        move_limit: options.moves,
+        start,
+        starting_tiles,
+        goal: options.goal,
+        daily_seed: daily.map(Date::seed),
+        seed: Some(seed),
+        last_move: None,
+        hint_until: None,
+        won: false,
+        celebrating: false,
+        best_tile: None,
+        over: false,
+        combo: options.combo,
+        confirm_quit: !options.no_confirm,
+        quitting: false,
+        key_repeat: KeyRepeat::new(REPEAT_INTERVAL),
+        swipe_from: None,
+        autoplay: options.auto || replay.is_some(),
+        autoplay_at: Instant::now(),
+        high_score,
+        high_score_path,
+        save_path: default_save_path(),
+        recording: options.record.map(|path| (path, Recording::new(seed))),
+        replay: replay.map(|recording| recording.actions.into_iter()),
+    };
+    app.autoplay_at += app.step_delay();
+    // Whatever a fixed start begins with is already there, rather than made
+    app.best_tile = app.game.board().max_tile();
+    app.game.set_auto_spawn(!options.no_spawn);
+    if let Some(fours) = options.fours {
+        app.game.set_four_probability(fours);
+    }
+    app.game.set_target(options.target.unwrap_or(Square::WIN));
+    app.game.set_time_limit(options.time);
+    if options.combo {
+        app.game.set_scoring_rule(Box::new(Combo));
+    }
+    app.game.clock_mut().start(Instant::now());
+    app.draw()?;
+    app.pacer.frame_shown(Instant::now());
+
+    loop {
+        let deadline = app.deadline();
+        if let Some(event) = next_event(deadline)? {
+            if app.handle_event(event)? == Flow::Stop {
+                break;
+            }
+        }
+        // Checked after every event as well as when the wait runs out, so that a stream of input
+        // like mouse movement can't hold anything up
+        let now = Instant::now();
+        if deadline.is_some_and(|deadline| deadline <= now) && app.tick(now)? == Flow::Stop {
+            break;
+        }
+    }
+
+    let App {
+        game,
+        renderer,
+        recording,
+        ..
+    } = app;
+    // Put the terminal back to normal before printing anything else
+    drop(renderer);
+    if let Some((path, recording)) = recording {
+        if let Err(error) = recording.save(&path) {
+            eprintln!(
+                "2048: couldn't save the recording to {}: {}",
+                path.display(),
+                error
+            );
+        }
     }
+    let max_tile = game.board().max_tile().map_or(0, Square::value);
+    println!(
+        "Moves: {}, merges: {}, final score: {}, highest tile: {}",
+        game.moves_made(),
+        game.merges_made(),
+        game.score(),
+        max_tile
+    );
 
     Ok(())
 }
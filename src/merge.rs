@@ -0,0 +1,55 @@
+//! The rules for which tiles merge when they slide into each other, and what they make.
+
+use crate::Square;
+
+/// Decides when two neighbouring tiles in a lane merge, and what they merge into.
+pub trait MergeRule {
+    /// Whether tile `b` merges into tile `a` when it slides up against it.
+    fn can_merge(a: Square, b: Square) -> bool;
+
+    /// The tile made by merging `b` into `a`. Only called when [MergeRule::can_merge] allows it.
+    fn merge(a: Square, b: Square) -> Square;
+
+    /// The number written on a tile, which is also what making it by a merge adds to the score.
+    fn value(square: Square) -> u32 {
+        square.value()
+    }
+}
+
+/// The usual rule: two equal tiles merge into one of twice the value.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Doubling;
+
+/// The Fibonacci variant, where neighbouring Fibonacci numbers merge into the next one, and the
+/// only equal tiles that merge are two 1s. The squares count along the sequence 1, 2, 3, 5, 8...,
+/// so the smallest square is a 1 and is the only one that can merge with itself.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fibonacci;
+
+impl MergeRule for Doubling {
+    fn can_merge(a: Square, b: Square) -> bool {
+        a == b
+    }
+
+    fn merge(a: Square, _: Square) -> Square {
+        a.inc()
+    }
+}
+
+impl MergeRule for Fibonacci {
+    fn can_merge(a: Square, b: Square) -> bool {
+        a.0.abs_diff(b.0) == 1 || a.0 == 0 && b.0 == 0
+    }
+
+    fn merge(a: Square, b: Square) -> Square {
+        a.max(b).inc()
+    }
+
+    fn value(square: Square) -> u32 {
+        let (mut current, mut next) = (1, 2);
+        for _ in 0..square.0 {
+            (current, next) = (next, current + next);
+        }
+        current
+    }
+}
@@ -0,0 +1,83 @@
+//! Logs of games that can be played back. As the tiles come from a seeded random number generator,
+//! the seed and what the player did are enough to get every board back exactly.
+//!
+//! The log is plain text: the seed on the first line, then one line for each thing the player did,
+//! which is `U`, `D`, `L` or `R` for a move, `undo` for taking one back and `redo` for making an
+//! undone move again.
+
+use crate::{Move, ParseMoveError};
+use std::{fs, io, path::Path};
+
+/// Something the player did that changed the game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Move(Move),
+    Undo,
+    Redo,
+}
+
+/// Everything needed to play a game again.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recording {
+    /// What the game's random number generator was seeded with
+    pub seed: u64,
+    /// What the player did, in order. Only moves that changed the board are included.
+    pub actions: Vec<Action>,
+}
+
+impl Recording {
+    /// Start recording a game that was seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Recording {
+            seed,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Read a recording from a file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let seed = lines
+            .next()
+            .ok_or_else(|| invalid("the recording is empty".to_string()))?;
+        let seed = seed
+            .parse()
+            .map_err(|_| invalid(format!("{:?} is not a seed", seed)))?;
+
+        let actions = lines
+            .map(|line| match line {
+                "undo" => Ok(Action::Undo),
+                "redo" => Ok(Action::Redo),
+                other => other
+                    .parse()
+                    .map(Action::Move)
+                    .map_err(|error: ParseMoveError| invalid(error.to_string())),
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Recording { seed, actions })
+    }
+
+    /// Write the recording to a file.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut contents = format!("{}\n", self.seed);
+        for action in &self.actions {
+            let line = match action {
+                Action::Move(Move::Up) => "U",
+                Action::Move(Move::Down) => "D",
+                Action::Move(Move::Left) => "L",
+                Action::Move(Move::Right) => "R",
+                Action::Undo => "undo",
+                Action::Redo => "redo",
+            };
+            contents += line;
+            contents += "\n";
+        }
+        fs::write(path, contents)
+    }
+}
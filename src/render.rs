@@ -0,0 +1,685 @@
+//! Drawing the game on a [Backend], which is usually a terminal.
+
+use crate::{
+    Backend, Board, Borders, CrosstermBackend, Justify, Layout, Move, Square, Stats, Theme,
+    MAX_DIGIT_WIDTH,
+};
+use std::{io::Write, iter::zip, time::Duration};
+
+/// A length of time as it's shown on a clock, like 1:05, leaving out the hours if there are none.
+fn clock_text(time: Duration) -> String {
+    let seconds = time.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match hours {
+        0 => format!("{}:{:02}", minutes, seconds),
+        _ => format!("{}:{:02}:{:02}", hours, minutes, seconds),
+    }
+}
+
+/// How to animate tiles sliding across the board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Animation {
+    /// How many in-between boards to show before the final one
+    pub frames: u32,
+    /// How long each in-between board stays on the screen
+    pub frame_time: Duration,
+}
+
+/// How to draw a tile for a moment, to draw attention to it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Emphasis {
+    /// As usual
+    Plain,
+    /// Brighter, for a tile just made by a merge
+    Popped,
+    /// Its colours without the number, for a tile that's only just appeared
+    Unlabelled,
+    /// Part way from the colours of the tier below to its own, `step` out of `steps`, for a tile
+    /// just made by a merge
+    Fading { step: u32, steps: u32 },
+}
+
+/// Puts boards on the screen of a backend, only redrawing what's changed
+pub struct Renderer<B: Backend, const W: usize = 4, const H: usize = W> {
+    backend: B,
+    size: (u16, u16),
+    /// What's currently on the screen, if anything
+    old_board: Option<Board<W, H>>,
+    old_stats: Option<Stats>,
+    /// Whether to move the cursor out of the way after each draw, for terminals that won't hide it
+    park_cursor: bool,
+    theme: Theme,
+    /// Whether the note asking for a bigger window is up, in place of the game
+    too_small: bool,
+    layout: Layout<W, H>,
+    /// How to animate moves, if at all
+    animation: Option<Animation>,
+    /// Whether tiles made by a merge fade from the colours of the tiles that went into them to
+    /// their own, when moves are animated
+    fade: bool,
+    /// Whether the game has the whole screen to itself, and is drawn in the middle of it
+    centred: bool,
+    /// How far down and across the header's top left corner is from the top left of the game's
+    /// rows
+    origin: (u16, u16),
+    /// Whether to show how many moves are made each second, alongside the time
+    show_rate: bool,
+    /// Whether to write numbers in big characters, which is only done while the window has room
+    /// for them
+    big: bool,
+    /// How many characters across each square is when the window has room, with narrower squares
+    /// used when it doesn't
+    cell_width: u16,
+}
+
+impl<Output: Write, const W: usize, const H: usize> Renderer<CrosstermBackend<Output>, W, H> {
+    /// Create a renderer from a stdout handle, drawing tiles in the classic colours.
+    pub fn new(output: Output) -> crossterm::Result<Self> {
+        Self::with_theme(output, Theme::classic())
+    }
+
+    /// Create a renderer from a stdout handle, drawing tiles with the given theme.
+    pub fn with_theme(output: Output, theme: Theme) -> crossterm::Result<Self> {
+        Self::with_backend(CrosstermBackend::new(output)?, theme)
+    }
+}
+
+impl<B: Backend, const W: usize, const H: usize> Renderer<B, W, H> {
+    /// The row the stats go on
+    const HEADER_ROW: u16 = 0;
+    /// The row the top of the board goes on
+    const BOARD_TOP: u16 = 1;
+
+    /// Create a renderer that draws on the given backend, with tiles in the given theme.
+    pub fn with_backend(backend: B, theme: Theme) -> crossterm::Result<Self> {
+        let mut renderer = Renderer {
+            backend,
+            size: (0, 0),
+            old_board: None,
+            old_stats: None,
+            park_cursor: false,
+            theme,
+            too_small: false,
+            layout: Layout::default(),
+            animation: None,
+            fade: false,
+            centred: false,
+            origin: (0, 0),
+            show_rate: false,
+            big: false,
+            cell_width: MAX_DIGIT_WIDTH,
+        };
+
+        // Push the screen up enough rows for the header, board and message line, so that we have
+        // space to play our game at the bottom of the screen.
+        renderer.backend.reserve_rows(renderer.message_row() + 1)?;
+
+        let size = renderer.backend.size()?;
+        renderer.resize(size)?;
+
+        Ok(renderer)
+    }
+
+    /// What the game is being drawn on.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// What the game is being drawn on, for drawing something else over it. The game doesn't know
+    /// what was drawn, so it needs [Renderer::force_redraw] to put itself back.
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Handle a resize event - note that to finish handling the resize event you will also need to
+    /// redraw the board.
+    pub fn resize(&mut self, new_size: (u16, u16)) -> crossterm::Result<()> {
+        self.size = new_size;
+        self.old_board = None;
+        self.old_stats = None;
+        if self.centred {
+            // There's no telling where the terminal has moved everything, so start again
+            self.clear_screen()?;
+        }
+        self.fit_layout()?;
+        self.update_origin();
+
+        Ok(())
+    }
+
+    /// Choose whether to write the numbers on tiles in big characters, several rows tall, for
+    /// reading from a distance. The game falls back to the usual numbers whenever the window is too
+    /// small for the big ones.
+    pub fn set_big(&mut self, big: bool) -> crossterm::Result<()> {
+        self.big = big;
+        self.fit_layout()?;
+        self.update_origin();
+        Ok(())
+    }
+
+    /// Lay the game out as big as the window allows, making room for the game's new size if that
+    /// changes. Big numbers come first if they were asked for, then the usual numbers in squares as
+    /// wide as asked for, then narrower and narrower squares, and last of all squares that only
+    /// show the exponent. If even those don't fit, they're kept and the too small note is shown.
+    fn fit_layout(&mut self) -> crossterm::Result<()> {
+        let old_layout = self.layout;
+        let old_message_row = self.message_row();
+        let big = self.big.then_some((true, self.cell_width, false));
+        let narrower = (3..=self.cell_width)
+            .rev()
+            .map(|cell_width| (false, cell_width, false));
+        let candidates = big.into_iter().chain(narrower).chain([(false, 3, true)]);
+        for (big, cell_width, compact) in candidates {
+            self.layout.big = big;
+            self.layout.cell_width = cell_width;
+            self.layout.compact = compact;
+            if self.fits() {
+                break;
+            }
+        }
+
+        if self.layout == old_layout {
+            return Ok(());
+        }
+        // Every square is a different size, so draw them all again
+        self.old_board = None;
+        if self.message_row() == old_message_row {
+            return Ok(());
+        }
+
+        if self.centred {
+            self.clear_screen()?;
+        } else {
+            // Blank everything the bigger of the two layouts covered, as the smaller one won't
+            // draw over all of it
+            self.backend.reserve_rows(self.message_row() + 1)?;
+            for row in 0..=self.message_row().max(old_message_row) {
+                self.clear_line(row)?;
+            }
+        }
+        self.old_stats = None;
+        Ok(())
+    }
+
+    /// Choose whether to take over the whole screen and draw the game in the middle of it,
+    /// instead of at the bottom against the left edge.
+    pub fn set_centred(&mut self, centred: bool) -> crossterm::Result<()> {
+        self.centred = centred;
+        self.resize(self.size)
+    }
+
+    /// Blank the screen and make its top row the top of the game's rows.
+    fn clear_screen(&mut self) -> crossterm::Result<()> {
+        self.backend.clear_screen()?;
+        self.old_board = None;
+        self.old_stats = None;
+        Ok(())
+    }
+
+    /// Work out where the game goes, which only moves from the top left when it's centred and
+    /// there's room for it.
+    fn update_origin(&mut self) {
+        self.origin = if self.centred && self.fits() {
+            let (columns, rows) = self.min_size();
+            ((self.size.1 - rows) / 2, (self.size.0 - columns) / 2)
+        } else {
+            (0, 0)
+        };
+    }
+
+    /// Choose whether to park the cursor below the board after each draw, instead of relying on the
+    /// terminal to hide it. Terminals don't say whether they can hide it, so this is up to the
+    /// player.
+    pub fn set_park_cursor(&mut self, park_cursor: bool) {
+        self.park_cursor = park_cursor;
+    }
+
+    /// Choose whether to show how many moves a second the game has gone at, for games that show
+    /// their time.
+    pub fn set_show_rate(&mut self, show_rate: bool) {
+        self.show_rate = show_rate;
+        self.old_stats = None;
+    }
+
+    /// Choose how many characters across each square is. Big tiles are shortened to thousands
+    /// when they don't fit, so the width must be at least 3. Narrower squares are still used when
+    /// the window is too narrow for these.
+    pub fn set_cell_width(&mut self, cell_width: u16) -> crossterm::Result<()> {
+        assert!(cell_width >= 3, "Cells must be at least 3 characters wide");
+        self.cell_width = cell_width;
+        self.fit_layout()?;
+        self.update_origin();
+        Ok(())
+    }
+
+    /// Choose where the number goes in each square. Every square is drawn again, so that they all
+    /// line up the same way.
+    pub fn set_justify(&mut self, justify: Justify) {
+        self.layout.justify = justify;
+        self.old_board = None;
+    }
+
+    /// Choose whether squares show the exponent they hold, 0 for a 2 and so on, instead of the
+    /// number on the tile, for seeing how tiles are stored.
+    pub fn set_exponents(&mut self, exponents: bool) {
+        self.layout.exponents = exponents;
+        self.old_board = None;
+    }
+
+    /// Choose whether to animate tiles sliding in [Renderer::draw_board_animated], or to just show
+    /// where they end up.
+    pub fn set_animation(&mut self, animation: Option<Animation>) {
+        self.animation = animation;
+    }
+
+    /// Choose whether tiles made by a merge fade into their colours from those of the tiles that
+    /// went into them, over as many frames as the slide. Only animated moves fade.
+    pub fn set_fade(&mut self, fade: bool) {
+        self.fade = fade;
+    }
+
+    /// Choose what to draw around and between the squares. Borders make the board taller, so
+    /// everything above the game is scrolled up to make room.
+    pub fn set_borders(&mut self, borders: Borders) -> crossterm::Result<()> {
+        self.layout.borders = borders;
+        self.fit_layout()?;
+        if self.centred {
+            // The game already has the whole screen
+            self.clear_screen()?;
+        } else {
+            self.backend.reserve_rows(self.message_row() + 1)?;
+        }
+        self.old_board = None;
+        self.old_stats = None;
+        self.update_origin();
+        Ok(())
+    }
+
+    /// The row for messages, just below the board.
+    fn message_row(&self) -> u16 {
+        Self::BOARD_TOP + self.layout.board_height()
+    }
+
+    /// Where on the screen the given square starts, as a row counted from the header down and a
+    /// column.
+    fn cell_position(&self, row_id: usize, col_id: usize) -> (u16, u16) {
+        let (row, column) = self.layout.cell_position(row_id, col_id);
+        (Self::BOARD_TOP + row, column)
+    }
+
+    /// The smallest window, in columns and rows, that the game can be drawn in.
+    fn min_size(&self) -> (u16, u16) {
+        (self.layout.board_width(), self.message_row() + 1)
+    }
+
+    /// Whether the window is big enough to draw the game in.
+    fn fits(&self) -> bool {
+        let (columns, rows) = self.min_size();
+        self.size.0 >= columns && self.size.1 >= rows
+    }
+
+    /// Replace the game with a note asking for a bigger window, centred in whatever part of the
+    /// game's rows is still on the screen.
+    fn draw_too_small(&mut self) -> crossterm::Result<()> {
+        let (min_columns, min_rows) = self.min_size();
+        let message_row = self.message_row();
+        // The game's rows start at the top of the screen when it's centred, and otherwise end at
+        // the bottom
+        let (top, bottom) = if self.centred {
+            (0, message_row.min(self.size.1.saturating_sub(1)))
+        } else {
+            (min_rows.saturating_sub(self.size.1), message_row)
+        };
+        for row in top..=bottom {
+            self.clear_line(row)?;
+        }
+
+        let text = format!(
+            "Terminal too small - resize to at least {}x{}",
+            min_columns, min_rows
+        );
+        let visible = text.chars().take(self.visible_width()).collect::<String>();
+        let column = self.size.0.saturating_sub(visible.chars().count() as u16) / 2;
+        self.draw_text(top + (bottom - top) / 2, column, &visible)?;
+
+        // Make sure everything is drawn from scratch once there's room again
+        self.old_board = None;
+        self.old_stats = None;
+        self.too_small = true;
+        self.backend.flush()
+    }
+
+    /// Blank the given row, counted from the header down, from the left of the game to the right
+    /// of the screen.
+    fn clear_line(&mut self, row: u16) -> crossterm::Result<()> {
+        self.backend.clear_line(row + self.origin.0, self.origin.1)
+    }
+
+    /// Write text at the given row and column, counted from the header's top left corner.
+    fn draw_text(&mut self, row: u16, column: u16, text: &str) -> crossterm::Result<()> {
+        self.backend
+            .draw_text(row + self.origin.0, column + self.origin.1, text)
+    }
+
+    /// Leave the cursor below the game, if the backend can't hide it.
+    fn park(&mut self) -> crossterm::Result<()> {
+        if self.park_cursor {
+            self.backend
+                .park_cursor(self.message_row() + self.origin.0, self.origin.1)?;
+        }
+        Ok(())
+    }
+
+    /// How many columns there are from the left of the game to the right of the screen.
+    fn visible_width(&self) -> usize {
+        self.size.0.saturating_sub(self.origin.1) as usize
+    }
+
+    /// Draw the given square of the board, with the given emphasis, or blank it if there's no tile
+    /// in it.
+    fn draw_cell(
+        &mut self,
+        row_id: usize,
+        col_id: usize,
+        cell: Option<Square>,
+        emphasis: Emphasis,
+    ) -> crossterm::Result<()> {
+        let (row, column) = self.cell_position(row_id, col_id);
+        let (row, column) = (row + self.origin.0, column + self.origin.1);
+        match cell {
+            Some(cell) => {
+                let style = self.theme.style(cell);
+                let (style, lines) = match emphasis {
+                    Emphasis::Plain => (style, self.layout.cell_lines(Some(cell))),
+                    Emphasis::Popped => (style.popped(), self.layout.cell_lines(Some(cell))),
+                    Emphasis::Unlabelled => (style, self.layout.cell_lines(None)),
+                    Emphasis::Fading { step, steps } => {
+                        // A merge made this tile out of two from the tier below
+                        let below = self.theme.style(Square(cell.0.saturating_sub(1)));
+                        let style = below.blended(style, step, steps);
+                        (style, self.layout.cell_lines(Some(cell)))
+                    }
+                };
+                for (line_row, text) in (row..).zip(&lines) {
+                    self.backend.draw_cell(line_row, column, text, style)?;
+                }
+                Ok(())
+            }
+            None => {
+                // Deliberately write spaces instead of moving past, so nothing is left behind
+                for line_row in row..row + self.layout.cell_height() {
+                    self.backend
+                        .clear_cell(line_row, column, self.layout.cell_columns())?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Show a line of text below the board, replacing whatever was there before. Text that doesn't
+    /// fit in the window is cut off.
+    pub fn message(&mut self, text: &str) -> crossterm::Result<()> {
+        if !self.fits() {
+            return Ok(());
+        }
+        self.clear_line(self.message_row())?;
+        let visible = text.chars().take(self.visible_width()).collect::<String>();
+        self.draw_text(self.message_row(), 0, &visible)?;
+        self.backend.flush()
+    }
+
+    /// Mark the game as over, replacing the board with how it went under the given title and
+    /// asking what to do next. The board is drawn from scratch the next time, so nothing of this is
+    /// left behind.
+    pub fn end_screen(
+        &mut self,
+        title: &str,
+        stats: &Stats,
+        max_tile: u32,
+    ) -> crossterm::Result<()> {
+        if !self.fits() {
+            return Ok(());
+        }
+        let mut lines = vec![
+            title.to_string(),
+            format!("Score: {}  Best: {}", stats.score, stats.best),
+            format!("Highest tile: {}  Moves: {}", max_tile, stats.moves),
+        ];
+        if let Some(elapsed) = stats.elapsed {
+            lines.push(format!("Time: {}", clock_text(elapsed)));
+        }
+
+        let height = self.layout.board_height();
+        let top = Self::BOARD_TOP + height.saturating_sub(lines.len() as u16) / 2;
+        for row in Self::BOARD_TOP..self.message_row() {
+            self.clear_line(row)?;
+        }
+        for (row, line) in (top..self.message_row()).zip(&lines) {
+            // Clamp to the left edge when the line is wider than the board
+            let column = self
+                .layout
+                .board_width()
+                .saturating_sub(line.chars().count() as u16)
+                / 2;
+            let visible = line
+                .chars()
+                .take(self.visible_width().saturating_sub(column.into()))
+                .collect::<String>();
+            self.draw_text(row, column, &visible)?;
+        }
+        self.old_board = None;
+        self.message("Press r to restart or Esc to quit")
+    }
+
+    /// Ring the terminal's bell. It goes out along with whatever is drawn next.
+    pub fn bell(&mut self) -> crossterm::Result<()> {
+        self.backend.bell()
+    }
+
+    /// Flash the whole screen for a moment.
+    pub fn flash(&mut self) -> crossterm::Result<()> {
+        self.backend.flash()
+    }
+
+    /// Draw the border line above the given row of squares, or below the last row when given `H`.
+    /// Borders never change, so this is only needed when drawing the board from scratch.
+    fn draw_border_line(&mut self, line: usize) -> crossterm::Result<()> {
+        let Some(text) = self.layout.border_line(line) else {
+            return Ok(());
+        };
+
+        let row = Self::BOARD_TOP + self.layout.border_row(line);
+        self.clear_line(row)?;
+        self.draw_text(row, 0, &text)
+    }
+
+    /// Draw the stats header, if it has changed.
+    fn draw_stats(&mut self, stats: &Stats) -> crossterm::Result<()> {
+        if self.old_stats.as_ref() == Some(stats) {
+            return Ok(());
+        }
+
+        self.clear_line(Self::HEADER_ROW)?;
+        let mut text = format!(
+            "Score: {}  Sum: {}  Best: {}  Moves: {}",
+            stats.score, stats.board_sum, stats.best, stats.moves
+        );
+        if let Some(moves_left) = stats.moves_left {
+            text += &format!("  Left: {}", moves_left);
+        }
+        // A countdown takes the place of the time so far
+        match (stats.time_left, stats.elapsed) {
+            (Some(time_left), _) => text += &format!("  Time left: {}", clock_text(time_left)),
+            (None, Some(elapsed)) => text += &format!("  Time: {}", clock_text(elapsed)),
+            (None, None) => {}
+        }
+        if let Some(elapsed) = stats.elapsed {
+            if self.show_rate && !elapsed.is_zero() {
+                let rate = f64::from(stats.moves) / elapsed.as_secs_f64();
+                text += &format!(" ({:.1}/s)", rate);
+            }
+        }
+        if let Some(multiplier) = stats.combo {
+            text += &format!("  Combo x{}", multiplier);
+        }
+        if stats.no_spawns {
+            text += "  No new tiles";
+        }
+        if let Some(spawns) = &stats.spawns {
+            text += "  ";
+            text += spawns;
+        }
+        let visible = text.chars().take(self.visible_width()).collect::<String>();
+        self.draw_text(Self::HEADER_ROW, 0, &visible)?;
+
+        self.old_stats = Some(stats.clone());
+        Ok(())
+    }
+
+    /// Draw the board that the given move led to, first showing the tiles on the screen sliding
+    /// into place if animations are turned on, and then the tile added afterwards at `new_tile`
+    /// appearing, if there was one. Falls back to [Renderer::draw_board] when there is nothing on
+    /// the screen to animate from.
+    pub fn draw_board_animated(
+        &mut self,
+        board: &Board<W, H>,
+        stats: &Stats,
+        last_move: Move,
+        new_tile: Option<(usize, usize)>,
+    ) -> crossterm::Result<()> {
+        if let (Some(animation), Some(old_board)) = (self.animation, self.old_board) {
+            let steps = animation.frames + 1;
+            for step in 1..steps {
+                // Each frame goes through the usual diffing, so the screen stays in step with
+                // old_board the whole way through
+                self.draw_board(&old_board.slide_frame(last_move, step, steps), stats)?;
+                std::thread::sleep(animation.frame_time);
+            }
+            // Where the tiles landed, before the new one was added
+            let mut landed = *board;
+            if let Some((x, y)) = new_tile {
+                landed[(x, y)] = None;
+            }
+            self.draw_board(&landed, stats)?;
+
+            if self.fits() {
+                let merged_cells = old_board.apply_move_detailed(last_move).merged_cells;
+                if self.fade {
+                    for step in 1..steps {
+                        let fading = Emphasis::Fading { step, steps };
+                        self.draw_merges(&landed, &merged_cells, fading)?;
+                        std::thread::sleep(animation.frame_time);
+                    }
+                }
+                self.draw_merges(&landed, &merged_cells, Emphasis::Popped)?;
+                std::thread::sleep(animation.frame_time);
+                self.draw_merges(&landed, &merged_cells, Emphasis::Plain)?;
+
+                if let Some((x, y)) = new_tile {
+                    // The new tile's colours come first and its number after, so that it's easy
+                    // to spot. Left out of old_board, so the final draw puts the number in.
+                    self.draw_cell(y, x, board[(x, y)], Emphasis::Unlabelled)?;
+                    self.park()?;
+                    self.backend.flush()?;
+                    std::thread::sleep(animation.frame_time);
+                }
+            }
+        }
+        self.draw_board(board, stats)
+    }
+
+    /// Redraw the tiles that a move merged with the given emphasis, such as popped out or back to
+    /// normal. This leaves old_board alone, as the same tiles are on the screen either way.
+    fn draw_merges(
+        &mut self,
+        board: &Board<W, H>,
+        merged_cells: &[[bool; W]; H],
+        emphasis: Emphasis,
+    ) -> crossterm::Result<()> {
+        for (row_id, (row, merged_row)) in zip(board.rows, merged_cells).enumerate() {
+            for (col_id, (cell, &merged)) in zip(row, merged_row).enumerate() {
+                if merged && cell.is_some() {
+                    self.draw_cell(row_id, col_id, cell, emphasis)?;
+                }
+            }
+        }
+
+        self.park()?;
+        self.backend.flush()
+    }
+
+    /// Draw the current board on the screen, along with the stats above it.
+    pub fn draw_board(&mut self, board: &Board<W, H>, stats: &Stats) -> crossterm::Result<()> {
+        if !self.fits() {
+            return self.draw_too_small();
+        }
+        if self.too_small {
+            // The note might have been on the message row, which the board doesn't cover
+            self.too_small = false;
+            self.message("")?;
+        }
+
+        self.draw_stats(stats)?;
+
+        if let Some(old_board) = self.old_board {
+            for (row_id, rows) in zip(old_board.rows, board.rows).enumerate() {
+                for (col_id, (old, new)) in zip(rows.0, rows.1).enumerate() {
+                    if old != new {
+                        self.draw_cell(row_id, col_id, new, Emphasis::Plain)?;
+                    }
+                }
+            }
+        } else {
+            for (row_id, row) in board.rows.iter().enumerate() {
+                self.draw_border_line(row_id)?;
+                let (top, _) = self.cell_position(row_id, 0);
+                for screen_row in top..top + self.layout.cell_height() {
+                    // Whatever was on screen before, such as the too small note, is no longer
+                    // known
+                    self.clear_line(screen_row)?;
+                    if let Some(vertical) = self.layout.borders.vertical() {
+                        for col_id in 0..W {
+                            let (_, screen_col) = self.cell_position(row_id, col_id);
+                            self.draw_text(screen_row, screen_col - 1, &vertical.to_string())?;
+                        }
+                        let right_edge = self.layout.board_width() - 1;
+                        self.draw_text(screen_row, right_edge, &vertical.to_string())?;
+                    }
+                }
+
+                for (col_id, &cell) in row.iter().enumerate() {
+                    self.draw_cell(row_id, col_id, cell, Emphasis::Plain)?;
+                }
+            }
+            self.draw_border_line(H)?;
+        }
+
+        self.park()?;
+
+        self.old_board = Some(*board);
+        self.backend.flush()
+    }
+
+    /// Blank the header and board and draw them again from scratch, for when something else has
+    /// drawn over the game. The message below the board is left as it is.
+    pub fn force_redraw(&mut self, board: &Board<W, H>, stats: &Stats) -> crossterm::Result<()> {
+        if self.fits() {
+            for row in 0..self.message_row() {
+                self.clear_line(row)?;
+            }
+        }
+        self.old_board = None;
+        self.old_stats = None;
+        self.draw_board(board, stats)
+    }
+}
+
+impl<B: Backend, const W: usize, const H: usize> Drop for Renderer<B, W, H> {
+    fn drop(&mut self) {
+        // Leave the cursor below the game, wherever it was drawn, for whatever comes after
+        self.backend
+            .park_cursor(self.message_row() + self.origin.0, 0)
+            .ok();
+        self.backend.flush().ok();
+    }
+}
@@ -0,0 +1,105 @@
+//! Bug reports: everything about the game being played that could help work out what went wrong
+//! with it, written to a file that can be attached to an issue.
+//!
+//! The report is plain text, one `name: value` line for each thing it records, with the board in
+//! its compact form and the moves as the letters `U`, `D`, `L` and `R`.
+
+use crate::{
+    daily::{Date, SECONDS_PER_DAY},
+    Board, Move,
+};
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The state of a game at the moment something went wrong with it.
+#[derive(Clone, Debug)]
+pub struct Report<const W: usize = 4, const H: usize = W> {
+    /// What the game's random number generator was seeded with, if it's known. A game loaded from
+    /// a save carries on from part way through the generator's numbers instead.
+    pub seed: Option<u64>,
+    /// Whatever is different about the game from a classic one, such as `daily` or `combo`
+    pub mode: String,
+    pub board: Board<W, H>,
+    /// The moves that led to the board, oldest first
+    pub moves: Vec<Move>,
+    /// How big the terminal was, as columns and rows, if that could be found out
+    pub terminal_size: Option<(u16, u16)>,
+    /// When the report was made
+    pub time: SystemTime,
+}
+
+impl<const W: usize, const H: usize> Report<W, H> {
+    /// Write the report to a new file in `directory`, named after when it was made. Reports made
+    /// in the same second get a number after the time, as in `2048-report-1792071000-2.txt`, so
+    /// that one report never overwrites another. Returns where it went.
+    pub fn write_in(&self, directory: &Path) -> io::Result<PathBuf> {
+        for count in 1.. {
+            let name = match count {
+                1 => format!("2048-report-{}.txt", self.seconds()),
+                _ => format!("2048-report-{}-{}.txt", self.seconds(), count),
+            };
+            let path = directory.join(name);
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(self.to_string().as_bytes())?;
+                    return Ok(path);
+                }
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error),
+            }
+        }
+        unreachable!("there's always another number to try")
+    }
+
+    /// When the report was made, in seconds since the start of 1970.
+    fn seconds(&self) -> u64 {
+        self.time
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs())
+    }
+}
+
+impl<const W: usize, const H: usize> fmt::Display for Report<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.seconds();
+        let since_midnight = seconds % SECONDS_PER_DAY;
+        writeln!(f, "2048 bug report")?;
+        writeln!(f, "version: {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(
+            f,
+            "time: {} {:02}:{:02}:{:02} UTC",
+            Date::from_days_since_epoch(seconds / SECONDS_PER_DAY),
+            since_midnight / 3600,
+            since_midnight / 60 % 60,
+            since_midnight % 60
+        )?;
+        match self.seed {
+            Some(seed) => writeln!(f, "seed: {}", seed)?,
+            None => writeln!(f, "seed: unknown")?,
+        }
+        writeln!(f, "mode: {}", self.mode)?;
+        writeln!(f, "size: {}x{}", W, H)?;
+        match self.terminal_size {
+            Some((columns, rows)) => writeln!(f, "terminal: {}x{}", columns, rows)?,
+            None => writeln!(f, "terminal: unknown")?,
+        }
+        writeln!(f, "score: {}", self.board.score())?;
+        writeln!(f, "board: {}", self.board.to_compact())?;
+        let moves = self
+            .moves
+            .iter()
+            .map(|direction| match direction {
+                Move::Up => 'U',
+                Move::Down => 'D',
+                Move::Left => 'L',
+                Move::Right => 'R',
+            })
+            .collect::<String>();
+        writeln!(f, "moves: {}", moves)
+    }
+}
@@ -0,0 +1,45 @@
+//! The rules for how much a move adds to the score.
+
+use crate::Square;
+
+/// Decides what a move adds to the score from the tiles its merges made.
+pub trait ScoringRule {
+    /// What a move adds to the score, given the tiles made by its merges in the order they were
+    /// made.
+    fn score(&self, merged: &[Square]) -> u32;
+
+    /// What the last merge of a move that made `merges` of them was multiplied by.
+    fn multiplier(&self, _merges: usize) -> u32 {
+        1
+    }
+}
+
+/// The usual rule: each merge adds the number on the tile it made.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Additive;
+
+/// The combo variant, which rewards moves that make a lot of merges at once. The first merge of a
+/// move adds the number on its tile, the second adds twice its number, the third three times, and
+/// so on.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Combo;
+
+impl ScoringRule for Additive {
+    fn score(&self, merged: &[Square]) -> u32 {
+        merged
+            .iter()
+            .fold(0, |total, square| total.saturating_add(square.value()))
+    }
+}
+
+impl ScoringRule for Combo {
+    fn score(&self, merged: &[Square]) -> u32 {
+        (1..).zip(merged).fold(0, |total, (multiplier, square)| {
+            total.saturating_add(square.value().saturating_mul(multiplier))
+        })
+    }
+
+    fn multiplier(&self, merges: usize) -> u32 {
+        merges.max(1).try_into().unwrap_or(u32::MAX)
+    }
+}
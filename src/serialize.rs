@@ -0,0 +1,49 @@
+//! Serde support, behind the `serde` feature. Squares are written as the number on the tile rather
+//! than their internal exponent, and boards as a grid of those numbers with `null` for an empty
+//! cell. The score is not part of a serialised board.
+
+use crate::{Board, Square, MAX_TILE_VALUE};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for Square {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.value())
+    }
+}
+
+impl<'de> Deserialize<'de> for Square {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u32::deserialize(deserializer)?;
+        Square::from_value(value).ok_or_else(|| {
+            D::Error::custom(format!(
+                "{} is not a power of two between 2 and {}",
+                value, MAX_TILE_VALUE
+            ))
+        })
+    }
+}
+
+impl<const W: usize, const H: usize> Serialize for Board<W, H> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rows.iter().map(|row| row.as_slice()))
+    }
+}
+
+impl<'de, const W: usize, const H: usize> Deserialize<'de> for Board<W, H> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<Option<Square>>>::deserialize(deserializer)?;
+        if rows.len() != H {
+            return Err(D::Error::invalid_length(rows.len(), &"one entry per row"));
+        }
+
+        let mut board = Board::new();
+        for (output, row) in board.rows.iter_mut().zip(rows) {
+            if row.len() != W {
+                return Err(D::Error::invalid_length(row.len(), &"one entry per column"));
+            }
+            output.copy_from_slice(&row);
+        }
+
+        Ok(board)
+    }
+}
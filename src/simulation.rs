@@ -0,0 +1,142 @@
+//! Playing whole games without a terminal, for measuring how well a strategy does and how fast the
+//! engine runs.
+
+use crate::{solver, Board, Game, Move, Square};
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+/// A strategy for playing the game.
+pub trait Policy {
+    /// Pick the next move for a board, or `None` to stop playing.
+    fn choose<const W: usize, const H: usize>(&mut self, board: &Board<W, H>) -> Option<Move>;
+}
+
+/// Plays any move that changes the board, picked at random.
+#[derive(Clone, Debug)]
+pub struct RandomPolicy<R: Rng> {
+    rng: R,
+}
+
+/// Plays the solver's best move, searching the given number of moves ahead.
+#[derive(Copy, Clone, Debug)]
+pub struct SolverPolicy {
+    pub depth: usize,
+}
+
+/// How a simulated game ended up.
+#[derive(Copy, Clone, Debug)]
+pub struct GameResult {
+    pub final_board: Board,
+    pub score: u32,
+    /// How many moves changed the board
+    pub moves: u32,
+    pub max_tile: Option<Square>,
+}
+
+impl<R: Rng> RandomPolicy<R> {
+    pub fn new(rng: R) -> Self {
+        RandomPolicy { rng }
+    }
+}
+
+impl<R: Rng> Policy for RandomPolicy<R> {
+    fn choose<const W: usize, const H: usize>(&mut self, board: &Board<W, H>) -> Option<Move> {
+        board.available_moves().choose(&mut self.rng)
+    }
+}
+
+impl Default for SolverPolicy {
+    fn default() -> Self {
+        SolverPolicy {
+            depth: solver::DEFAULT_DEPTH,
+        }
+    }
+}
+
+impl Policy for SolverPolicy {
+    fn choose<const W: usize, const H: usize>(&mut self, board: &Board<W, H>) -> Option<Move> {
+        solver::best_move_with_depth(board, self.depth)
+    }
+}
+
+/// Play a game from the start until it's over, with the policy choosing every move and `rng`
+/// placing the tiles. The game also ends if the policy stops, or picks a move that does nothing.
+pub fn simulate<R: Rng, P: Policy>(rng: &mut R, policy: &mut P) -> GameResult {
+    let mut game: Game<_> = Game::new(rng);
+    // Nothing is ever taken back, so there's no need to keep the history
+    game.set_undo_depth(0);
+
+    while let Some(direction) = policy.choose(game.board()) {
+        let result = game.step(direction);
+        if !result.moved || result.game_over {
+            break;
+        }
+    }
+
+    GameResult {
+        final_board: *game.board(),
+        score: game.score(),
+        moves: game.moves_made(),
+        max_tile: game.board().max_tile(),
+    }
+}
+
+/// A board that could come up in a real game, for testing against: two tiles to start with, then
+/// up to `moves` moves picked at random, each followed by a new tile. It stops early if the game
+/// is over.
+pub fn reachable_board<R: Rng, const W: usize, const H: usize>(
+    rng: &mut R,
+    moves: u32,
+) -> Board<W, H> {
+    let mut board = Board::new();
+    board.add_square(rng);
+    board.add_square(rng);
+    for _ in 0..moves {
+        let Some(direction) = board.available_moves().choose(rng) else {
+            break;
+        };
+        board = board.apply_move(direction);
+        board.add_square(rng);
+    }
+    board
+}
+
+/// Play `count` games, each with its own policy made by `policy_factory` from the game's index.
+/// The tiles for game `i` come from a generator seeded with `seed + i`, so the same arguments always
+/// give the same results, in the same order. With the `rayon` feature the games are spread across
+/// threads.
+pub fn simulate_many<P, F>(count: usize, seed: u64, policy_factory: F) -> Vec<GameResult>
+where
+    P: Policy,
+    F: Fn(usize) -> P + Sync,
+{
+    let play = |index: usize| {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(index as u64));
+        simulate(&mut rng, &mut policy_factory(index))
+    };
+
+    let games = 0..count;
+    #[cfg(feature = "rayon")]
+    let games = games.into_par_iter();
+    games.map(play).collect()
+}
+
+/// The average score of some games, or 0 if there weren't any.
+pub fn mean_score(results: &[GameResult]) -> f64 {
+    if results.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = results.iter().map(|result| f64::from(result.score)).sum();
+    total / results.len() as f64
+}
+
+/// How many games reached each max tile.
+pub fn max_tile_histogram(results: &[GameResult]) -> BTreeMap<Square, usize> {
+    let mut histogram = BTreeMap::new();
+    for max_tile in results.iter().filter_map(|result| result.max_tile) {
+        *histogram.entry(max_tile).or_insert(0) += 1;
+    }
+    histogram
+}
@@ -0,0 +1,67 @@
+//! The state of a game at one moment, as JSON for other programs to read. Each snapshot is a single
+//! line holding an object with these fields, always in this order:
+//!
+//! - `board`: the rows from top to bottom, each a list of cells from left to right, where a cell is
+//!   the number on its tile or `null` if it's empty. This is the same as a serialised board.
+//! - `score`: the score so far, as a whole number.
+//! - `moves`: how many moves have changed the board so far, as a whole number.
+//! - `game_over`: `true` if no move can change the board any more, otherwise `false`.
+//!
+//! For example, `{"board":[[2,null],[null,4]],"score":0,"moves":0,"game_over":false}`.
+
+use crate::{Board, Game};
+use rand::Rng;
+use std::fmt;
+
+/// What a game looked like after a move. Written with [fmt::Display] as one line of JSON, so that
+/// it doesn't need a JSON library, or serialised as the same object with the `serde` feature.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Snapshot<const W: usize = 4, const H: usize = W> {
+    pub board: Board<W, H>,
+    pub score: u32,
+    /// How many moves have changed the board so far
+    pub moves: u32,
+    /// Whether no move can change the board any more
+    pub game_over: bool,
+}
+
+impl<const W: usize, const H: usize> Snapshot<W, H> {
+    /// Take a snapshot of where a game is up to.
+    pub fn of<R: Rng>(game: &Game<R, W, H>) -> Self {
+        let board = *game.board();
+        Snapshot {
+            board,
+            score: game.score(),
+            moves: game.moves_made(),
+            game_over: board.is_game_over(),
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> fmt::Display for Snapshot<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{\"board\":[")?;
+        for (y, row) in self.board.rows.iter().enumerate() {
+            if y > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "[")?;
+            for (x, cell) in row.iter().enumerate() {
+                if x > 0 {
+                    write!(f, ",")?;
+                }
+                match cell {
+                    Some(square) => write!(f, "{}", square.value())?,
+                    None => write!(f, "null")?,
+                }
+            }
+            write!(f, "]")?;
+        }
+        write!(
+            f,
+            "],\"score\":{},\"moves\":{},\"game_over\":{}}}",
+            self.score, self.moves, self.game_over
+        )
+    }
+}
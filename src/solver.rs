@@ -0,0 +1,123 @@
+//! A computer player, which picks moves with a depth-limited expectimax search. The player's moves
+//! are maximised over, while the tile that spawns after each move is treated as a chance node.
+
+use crate::{Board, Move, DEFAULT_SPAWNS};
+
+/// How many of the player's moves to look ahead, unless told otherwise
+pub const DEFAULT_DEPTH: usize = 2;
+
+/// What a position with no moves left is worth, well below anything a heuristic should produce
+const LOSS: f64 = -1_000_000.0;
+
+/// A way of scoring how promising a board is for the player, used at the leaves of the search.
+pub trait Heuristic {
+    /// Score a board. Bigger is better, and only the relative order of scores matters.
+    fn evaluate<const W: usize, const H: usize>(&self, board: &Board<W, H>) -> f64;
+}
+
+/// The heuristic used by [best_move], which rewards empty cells, rows and columns that run in one
+/// direction, and neighbouring tiles that are close in value.
+#[derive(Copy, Clone, Debug)]
+pub struct StandardHeuristic {
+    pub empty_weight: f64,
+    pub monotonicity_weight: f64,
+    pub smoothness_weight: f64,
+}
+
+impl Default for StandardHeuristic {
+    fn default() -> Self {
+        StandardHeuristic {
+            empty_weight: 2.7,
+            monotonicity_weight: 1.0,
+            smoothness_weight: 0.1,
+        }
+    }
+}
+
+impl Heuristic for StandardHeuristic {
+    fn evaluate<const W: usize, const H: usize>(&self, board: &Board<W, H>) -> f64 {
+        self.empty_weight * board.count_empty() as f64
+            + self.monotonicity_weight * board.monotonicity() as f64
+            + self.smoothness_weight * board.smoothness() as f64
+    }
+}
+
+/// Pick the best move for a board, searching [DEFAULT_DEPTH] moves ahead. Returns `None` only when
+/// the game is already over.
+pub fn best_move<const W: usize, const H: usize>(board: &Board<W, H>) -> Option<Move> {
+    best_move_with_depth(board, DEFAULT_DEPTH)
+}
+
+/// Pick the best move for a board, searching the given number of moves ahead (at least one).
+pub fn best_move_with_depth<const W: usize, const H: usize>(
+    board: &Board<W, H>,
+    depth: usize,
+) -> Option<Move> {
+    best_move_with_heuristic(board, depth, &StandardHeuristic::default())
+}
+
+/// Pick the best move for a board, searching the given number of moves ahead (at least one) and
+/// judging the positions found with a custom heuristic.
+pub fn best_move_with_heuristic<const W: usize, const H: usize>(
+    board: &Board<W, H>,
+    depth: usize,
+    heuristic: &impl Heuristic,
+) -> Option<Move> {
+    let depth = depth.max(1);
+    board
+        .available_moves()
+        .map(|direction| {
+            let value = chance_node(board.apply_move(direction), depth - 1, heuristic);
+            (direction, value)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(direction, _)| direction)
+}
+
+/// The value of a board for the player to move on, with `depth` moves left to search.
+fn max_node<const W: usize, const H: usize>(
+    board: Board<W, H>,
+    depth: usize,
+    heuristic: &impl Heuristic,
+) -> f64 {
+    board
+        .available_moves()
+        .map(|direction| chance_node(board.apply_move(direction), depth, heuristic))
+        .max_by(f64::total_cmp)
+        .unwrap_or(LOSS)
+}
+
+/// The expected value of a board that is waiting for a tile to spawn, averaged over every place
+/// the tile could appear and both values it could have.
+fn chance_node<const W: usize, const H: usize>(
+    board: Board<W, H>,
+    depth: usize,
+    heuristic: &impl Heuristic,
+) -> f64 {
+    if depth == 0 {
+        return heuristic.evaluate(&board);
+    }
+
+    let mut total = 0.0;
+    let mut spaces = 0;
+    for y in 0..H {
+        for x in 0..W {
+            if board.rows[y][x].is_some() {
+                continue;
+            }
+            spaces += 1;
+
+            for (square, probability) in DEFAULT_SPAWNS {
+                let mut spawned = board;
+                spawned.rows[y][x] = Some(square);
+                total += probability * max_node(spawned, depth - 1, heuristic);
+            }
+        }
+    }
+
+    if spaces == 0 {
+        // Can't happen after a move that did anything, but there's nowhere to spawn a tile anyway
+        return max_node(board, depth - 1, heuristic);
+    }
+    total / spaces as f64
+}
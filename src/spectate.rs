@@ -0,0 +1,98 @@
+//! Codes for sharing a game for others to watch the solver play. A code holds everything needed
+//! to deal the same tiles again: the seed, how merges are scored and the size of the board.
+//!
+//! Codes are written as the mode, the size and the seed in hexadecimal, separated by dashes, such
+//! as `classic-4x4-2a`. Letters can be in either case. Only 4x4 games can be watched for now, so
+//! codes for any other size aren't read, and a code that reads is always one that can be played.
+
+use std::{fmt, str::FromStr};
+
+/// How merges are scored in the game being watched.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Each merge is worth the tile it makes
+    Classic,
+    /// Each merge in a move is worth more than the one before it
+    Combo,
+}
+
+/// Everything needed to set up a game to watch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpectateCode {
+    /// What the game's random number generator is seeded with
+    pub seed: u64,
+    pub mode: Mode,
+    /// How many squares across the board is
+    pub width: u8,
+    /// How many squares down the board is
+    pub height: u8,
+}
+
+/// Why a spectate code couldn't be read, holding the text that wasn't one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSpectateCodeError(pub String);
+
+impl Mode {
+    /// The name the mode goes by in a code.
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Classic => "classic",
+            Mode::Combo => "combo",
+        }
+    }
+}
+
+impl fmt::Display for SpectateCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}x{}-{:x}",
+            self.mode.name(),
+            self.width,
+            self.height,
+            self.seed
+        )
+    }
+}
+
+/// Reads the format written by [SpectateCode]'s `Display` impl. Whitespace around the code is
+/// ignored, and the board must be 4x4, the only size the solver plays.
+impl FromStr for SpectateCode {
+    type Err = ParseSpectateCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseSpectateCodeError(s.to_string());
+        let code = s.trim().to_ascii_lowercase();
+        let mut parts = code.split('-');
+        let (Some(mode), Some(size), Some(seed), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        let mode = [Mode::Classic, Mode::Combo]
+            .into_iter()
+            .find(|candidate| candidate.name() == mode)
+            .ok_or_else(invalid)?;
+        let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+        let side = |side: &str| side.parse::<u8>().ok().filter(|&side| side == 4);
+        let (Some(width), Some(height)) = (side(width), side(height)) else {
+            return Err(invalid());
+        };
+        let seed = u64::from_str_radix(seed, 16).map_err(|_| invalid())?;
+        Ok(SpectateCode {
+            seed,
+            mode,
+            width,
+            height,
+        })
+    }
+}
+
+impl fmt::Display for ParseSpectateCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} is not a spectate code", self.0)
+    }
+}
+
+impl std::error::Error for ParseSpectateCodeError {}
@@ -0,0 +1,216 @@
+use crate::Square;
+use crossterm::style::Color;
+
+/// How to draw a single tile. Colours that are `None` are left as the terminal's own.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TileStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+/// The styles to draw each tile with, indexed by the tile's exponent. Tiles beyond the end of the
+/// theme use its last style, with any RGB background a little darker for each tier past the end.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    tiles: Vec<TileStyle>,
+}
+
+impl TileStyle {
+    /// Dark text on a light background.
+    const fn dark(r: u8, g: u8, b: u8) -> Self {
+        TileStyle {
+            foreground: Some(Color::Black),
+            background: Some(Color::Rgb { r, g, b }),
+            bold: false,
+            underline: false,
+        }
+    }
+
+    /// Bold light text on a darker background.
+    const fn light(r: u8, g: u8, b: u8) -> Self {
+        TileStyle {
+            foreground: Some(Color::White),
+            background: Some(Color::Rgb { r, g, b }),
+            bold: true,
+            underline: false,
+        }
+    }
+
+    /// Bold text in one of the basic terminal colours.
+    const fn named(foreground: Color, background: Color) -> Self {
+        TileStyle {
+            foreground: Some(foreground),
+            background: Some(background),
+            bold: true,
+            underline: false,
+        }
+    }
+
+    /// The terminal's own colours, with only text attributes to tell tiles apart.
+    const fn plain(bold: bool, underline: bool) -> Self {
+        TileStyle {
+            foreground: None,
+            background: None,
+            bold,
+            underline,
+        }
+    }
+}
+
+impl TileStyle {
+    /// A brighter version of this style, for making a tile stand out for a moment. Only colours
+    /// given as RGB can be lightened; the rest stay as they are.
+    pub fn popped(self) -> Self {
+        let lighten = |channel: u8| channel + (u8::MAX - channel) / 3;
+        let background = match self.background {
+            Some(Color::Rgb { r, g, b }) => Some(Color::Rgb {
+                r: lighten(r),
+                g: lighten(g),
+                b: lighten(b),
+            }),
+            other => other,
+        };
+        TileStyle {
+            background,
+            bold: true,
+            ..self
+        }
+    }
+
+    /// The style `step` out of `steps` of the way from this one to `other`, for fading a tile from
+    /// one tier's colours to the next. Colours given as RGB are mixed; the rest switch over half
+    /// way, as do the text attributes.
+    pub fn blended(self, other: TileStyle, step: u32, steps: u32) -> Self {
+        let mix = |from: Option<Color>, to: Option<Color>| match (from, to) {
+            (
+                Some(Color::Rgb { r, g, b }),
+                Some(Color::Rgb {
+                    r: to_r,
+                    g: to_g,
+                    b: to_b,
+                }),
+            ) => {
+                let channel = |from: u8, to: u8| {
+                    let (from, to) = (i64::from(from), i64::from(to));
+                    (from + (to - from) * i64::from(step) / i64::from(steps)) as u8
+                };
+                Some(Color::Rgb {
+                    r: channel(r, to_r),
+                    g: channel(g, to_g),
+                    b: channel(b, to_b),
+                })
+            }
+            _ if 2 * step < steps => from,
+            _ => to,
+        };
+        let nearer = if 2 * step < steps { self } else { other };
+        TileStyle {
+            foreground: mix(self.foreground, other.foreground),
+            background: mix(self.background, other.background),
+            ..nearer
+        }
+    }
+
+    /// A darker version of this style, `steps` tiers deeper. Only colours given as RGB can be
+    /// darkened; the rest stay as they are.
+    fn deepened(self, steps: usize) -> Self {
+        // Each step takes a tenth off, which is as good as black well within 64 steps
+        let darken =
+            |channel: u8| (0..steps.min(64)).fold(channel, |channel, _| channel - channel / 10);
+        let background = match self.background {
+            Some(Color::Rgb { r, g, b }) => Some(Color::Rgb {
+                r: darken(r),
+                g: darken(g),
+                b: darken(b),
+            }),
+            other => other,
+        };
+        TileStyle { background, ..self }
+    }
+}
+
+impl Theme {
+    /// Create a theme from the styles for each tile, starting from 2. There must be at least one.
+    pub fn new(tiles: Vec<TileStyle>) -> Self {
+        assert!(!tiles.is_empty(), "A theme needs at least one tile style");
+        Theme { tiles }
+    }
+
+    /// The colours from the original game.
+    pub fn classic() -> Self {
+        Theme::new(vec![
+            TileStyle::dark(238, 228, 218),  // 2
+            TileStyle::dark(237, 224, 200),  // 4
+            TileStyle::light(242, 177, 121), // 8
+            TileStyle::light(245, 149, 99),  // 16
+            TileStyle::light(246, 124, 95),  // 32
+            TileStyle::light(246, 94, 59),   // 64
+            TileStyle::dark(237, 207, 114),  // 128
+            TileStyle::light(237, 204, 97),  // 256
+            TileStyle::light(237, 200, 80),  // 512
+            TileStyle::light(237, 197, 63),  // 1024
+            TileStyle::light(237, 194, 68),  // 2048
+            TileStyle::light(181, 134, 180), // 4096
+            TileStyle::light(168, 97, 171),  // 8192
+            TileStyle::light(160, 72, 163),  // 16 384
+            TileStyle::light(128, 0, 128),   // 32 768
+            TileStyle::light(96, 0, 70),     // 65 536
+        ])
+    }
+
+    /// Strongly contrasting colours that neighbouring tiers don't share, using only the basic
+    /// terminal palette.
+    pub fn high_contrast() -> Self {
+        Theme::new(vec![
+            TileStyle::named(Color::Black, Color::White),       // 2
+            TileStyle::named(Color::Black, Color::Yellow),      // 4
+            TileStyle::named(Color::White, Color::DarkRed),     // 8
+            TileStyle::named(Color::Black, Color::Cyan),        // 16
+            TileStyle::named(Color::White, Color::DarkMagenta), // 32
+            TileStyle::named(Color::Black, Color::Green),       // 64
+            TileStyle::named(Color::White, Color::DarkBlue),    // 128
+            TileStyle::named(Color::Black, Color::Red),         // 256
+            TileStyle::named(Color::White, Color::DarkGreen),   // 512
+            TileStyle::named(Color::Black, Color::Magenta),     // 1024
+            TileStyle::named(Color::Yellow, Color::Black),      // 2048
+            TileStyle::named(Color::Cyan, Color::Black),        // 4096 and up
+        ])
+    }
+
+    /// No colours at all, for terminals without them or players who can't tell them apart. Bigger
+    /// tiles are picked out with bold and underlined text.
+    pub fn mono() -> Self {
+        Theme::new(vec![
+            TileStyle::plain(false, false), // 2
+            TileStyle::plain(false, false), // 4
+            TileStyle::plain(true, false),  // 8
+            TileStyle::plain(true, false),  // 16
+            TileStyle::plain(true, false),  // 32
+            TileStyle::plain(true, false),  // 64
+            TileStyle::plain(false, true),  // 128
+            TileStyle::plain(false, true),  // 256
+            TileStyle::plain(false, true),  // 512
+            TileStyle::plain(false, true),  // 1024
+            TileStyle::plain(true, true),   // 2048 and up
+        ])
+    }
+
+    /// How to draw the given tile.
+    pub fn style(&self, square: Square) -> TileStyle {
+        let last = self.tiles.len() - 1;
+        let index = usize::from(square.0);
+        if index <= last {
+            self.tiles[index]
+        } else {
+            self.tiles[last].deepened(index - last)
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
@@ -0,0 +1,22 @@
+use play_2048::daily::Date;
+
+fn date(year: u32, month: u32, day: u32) -> Date {
+    Date { year, month, day }
+}
+
+#[test]
+fn days_since_the_epoch_become_dates() {
+    assert_eq!(Date::from_days_since_epoch(0), date(1970, 1, 1));
+    assert_eq!(Date::from_days_since_epoch(11_016), date(2000, 2, 29));
+    assert_eq!(Date::from_days_since_epoch(11_017), date(2000, 3, 1));
+    assert_eq!(Date::from_days_since_epoch(19_722), date(2023, 12, 31));
+    assert_eq!(Date::from_days_since_epoch(19_723), date(2024, 1, 1));
+}
+
+#[test]
+fn each_day_gets_its_own_seed() {
+    let day = date(2024, 1, 1);
+    assert_eq!(day.seed(), date(2024, 1, 1).seed());
+    assert_ne!(day.seed(), date(2024, 1, 2).seed());
+    assert_eq!(day.to_string(), "2024-01-01");
+}
@@ -0,0 +1,42 @@
+//! The lookup tables behind the fast moves, checked against the usual moves for every row of four
+//! squares there is.
+
+use play_2048::{Board, Move, Square};
+
+/// A board with `row` packed as four exponent nibbles, lowest first with 0 for an empty square,
+/// laid along the top row or down the left column.
+fn board_with_row(row: u16, across: bool) -> Board {
+    let mut board = Board::new();
+    for index in 0..4 {
+        let nibble = row >> (4 * index) & 0xf;
+        let cell = (nibble != 0).then(|| Square::from_value(1 << nibble).unwrap());
+        let position = if across { (index, 0) } else { (0, index) };
+        board[position] = cell;
+    }
+    board
+}
+
+#[test]
+fn every_row_moves_the_same_with_the_tables() {
+    for row in 0..=u16::MAX {
+        for (across, directions) in [
+            (true, [Move::Left, Move::Right]),
+            (false, [Move::Up, Move::Down]),
+        ] {
+            let board = board_with_row(row, across);
+            for direction in directions {
+                let fast = board.apply_move_fast(direction);
+                let expected = board.apply_move(direction);
+                assert!(
+                    fast.same_state(&expected),
+                    "{:?} gave\n{}\nscoring {} instead of\n{}\nscoring {}",
+                    direction,
+                    fast,
+                    fast.score(),
+                    expected,
+                    expected.score()
+                );
+            }
+        }
+    }
+}
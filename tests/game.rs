@@ -0,0 +1,250 @@
+use play_2048::{spawn_label, Board, ChaCha12Rng, Game, GameObserver, Move, Square};
+use rand::{
+    rngs::{mock::StepRng, StdRng},
+    Rng, SeedableRng,
+};
+use std::{cell::Cell, fs, io, rc::Rc};
+
+#[test]
+fn merges_are_counted_and_taken_back_with_undo() {
+    let board = "2 2 4 4\n8 8 . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let mut game = Game::from_board(StdRng::seed_from_u64(0), board);
+    game.step(Move::Left);
+    assert_eq!(game.merges_made(), 3);
+    assert_eq!(game.moves_made(), 1);
+
+    assert!(game.undo());
+    assert_eq!(game.merges_made(), 0);
+    assert_eq!(game.moves_made(), 0);
+}
+
+#[test]
+fn boards_are_equal_by_their_tiles_but_the_same_state_only_with_the_same_score() {
+    let merged = "4 . . .\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap()
+        .apply_move(Move::Left);
+    let made = "2 2 . .\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap()
+        .apply_move(Move::Left);
+    assert_eq!(merged, made);
+    assert!(!merged.same_state(&made));
+    assert!(made.same_state(&made));
+}
+
+struct Wins(Rc<Cell<u32>>);
+
+impl GameObserver for Wins {
+    fn on_win(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn the_game_is_won_by_making_its_target_tile() {
+    let board = "64 64 . .\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let mut game = Game::from_board(StdRng::seed_from_u64(0), board);
+    let wins = Rc::new(Cell::new(0));
+    game.add_observer(Box::new(Wins(wins.clone())));
+    game.set_target(Square::from_value(128).unwrap());
+    game.step(Move::Left);
+    assert_eq!(wins.get(), 1);
+}
+
+#[test]
+fn a_puzzle_is_solved_only_by_its_exact_arrangement() {
+    let goal = Board::from_compact("2,4,8,16/.,.,.,./.,.,.,./.,.,.,.").unwrap();
+    let solved = "2 4 8 16\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let unsolved = ". 2 4 8\n16 . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    assert!(solved.is_solved_against(&goal));
+    assert!(!unsolved.is_solved_against(&goal));
+    assert_eq!(goal.to_compact(), "2,4,8,16/.,.,.,./.,.,.,./.,.,.,.");
+}
+
+#[test]
+fn a_turn_reports_the_move_and_the_tile_added_after_it() {
+    let board = "2 2 . .\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let mut game = Game::from_board(StdRng::seed_from_u64(0), board);
+    assert!(game.play(Move::Up).is_none());
+    assert_eq!(game.moves_made(), 0);
+
+    let report = game.play(Move::Left).unwrap();
+    assert_eq!(report.outcome.score_gained, 4);
+    assert_eq!(game.score(), 4);
+    assert_eq!(game.moves_made(), 1);
+    let ((x, y), square) = report.spawned.unwrap();
+    assert_eq!(report.outcome.board[(x, y)], None);
+    assert_eq!(game.board()[(x, y)], Some(square));
+}
+
+#[test]
+fn the_history_keeps_moves_that_changed_the_board_until_they_are_undone() {
+    let board = "2 2 . .\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let mut game = Game::from_board(StdRng::seed_from_u64(0), board);
+    game.set_auto_spawn(false);
+    game.step(Move::Up);
+    game.step(Move::Right);
+    game.step(Move::Down);
+    assert_eq!(game.history(), [Move::Right, Move::Down]);
+
+    game.undo();
+    assert_eq!(game.history(), [Move::Right]);
+    game.clear_history();
+    assert!(game.history().is_empty());
+    assert!(game.undo());
+    assert!(game.history().is_empty());
+}
+
+#[test]
+fn a_loaded_game_carries_on_with_the_tiles_the_saved_one_would_have_had() {
+    let path = std::env::temp_dir().join(format!("2048-save-test-{}", std::process::id()));
+    let mut game = Game::<ChaCha12Rng>::new(ChaCha12Rng::seed_from_u64(7));
+    for direction in [Move::Left, Move::Up, Move::Right, Move::Down, Move::Left] {
+        game.step(direction);
+    }
+    game.save(&path).unwrap();
+    let mut loaded = Game::<ChaCha12Rng>::load(&path).unwrap();
+    assert!(loaded.board().same_state(game.board()));
+    assert_eq!(loaded.moves_made(), game.moves_made());
+    assert_eq!(loaded.merges_made(), game.merges_made());
+
+    for direction in [Move::Up, Move::Left, Move::Down, Move::Right] {
+        game.step(direction);
+        loaded.step(direction);
+        assert!(loaded.board().same_state(game.board()));
+    }
+
+    fs::write(&path, "2048 save 0\nseed 1\n").unwrap();
+    let error = Game::<ChaCha12Rng>::load(&path).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    fs::write(&path, "2048 save 1\nseed 1\n").unwrap();
+    let error = Game::<ChaCha12Rng>::load(&path).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn games_with_the_biggest_tiles_can_be_loaded_again() {
+    let path = std::env::temp_dir().join(format!("2048-save-big-test-{}", std::process::id()));
+    let board = "2 16384 . .\n. 32768 4 .\n. . . .\n. . 8 65536"
+        .parse::<Board>()
+        .unwrap();
+    let game = Game::from_board(ChaCha12Rng::seed_from_u64(1), board);
+    game.save(&path).unwrap();
+    let loaded = Game::<ChaCha12Rng>::load(&path);
+    fs::remove_file(&path).ok();
+    assert!(loaded.unwrap().board().same_state(game.board()));
+}
+
+#[test]
+fn games_with_tiles_past_65536_can_be_loaded_again() {
+    let path = std::env::temp_dir().join(format!("2048-save-huge-test-{}", std::process::id()));
+    let board = "131072 2 . .\n. 2147483648 . .\n. . . .\n. . . 2^40"
+        .parse::<Board>()
+        .unwrap();
+    assert_eq!(board[(0, 0)], Square::from_value(131072));
+    let game = Game::from_board(ChaCha12Rng::seed_from_u64(1), board);
+    game.save(&path).unwrap();
+    let loaded = Game::<ChaCha12Rng>::load(&path);
+    fs::remove_file(&path).ok();
+    assert!(loaded.unwrap().board().same_state(game.board()));
+}
+
+#[test]
+fn saveable_games_get_the_same_tiles_from_a_seed_as_ever() {
+    let mut saveable = ChaCha12Rng::seed_from_u64(2048);
+    let mut standard = StdRng::seed_from_u64(2048);
+    for _ in 0..100 {
+        assert_eq!(saveable.gen::<u64>(), standard.gen::<u64>());
+    }
+}
+
+#[test]
+fn redo_brings_back_exactly_what_was_undone_until_a_new_move_is_made() {
+    let mut game = Game::<StdRng>::new(StdRng::seed_from_u64(3));
+    game.step(Move::Left);
+    game.step(Move::Up);
+    let after = *game.board();
+    let moves = game.moves_made();
+
+    assert!(game.undo());
+    assert!(game.undo());
+    assert!(game.redo());
+    assert!(game.redo());
+    assert!(!game.redo());
+    assert!(game.board().same_state(&after));
+    assert_eq!(game.moves_made(), moves);
+    assert_eq!(game.history(), [Move::Left, Move::Up]);
+
+    assert!(game.undo());
+    game.step(Move::Right);
+    assert!(!game.redo());
+}
+
+#[test]
+fn moves_are_read_from_their_letter_or_name_in_either_case() {
+    assert_eq!(Move::try_from('u'), Ok(Move::Up));
+    assert_eq!(Move::try_from('R'), Ok(Move::Right));
+    assert!(Move::try_from('w').is_err());
+    assert_eq!("D".parse(), Ok(Move::Down));
+    assert_eq!(" left\n".parse(), Ok(Move::Left));
+    assert_eq!("UP".parse(), Ok(Move::Up));
+    assert_eq!(
+        "undo".parse::<Move>().unwrap_err().to_string(),
+        "\"undo\" is not a move"
+    );
+}
+
+#[test]
+fn a_mock_random_number_generator_decides_every_new_tile() {
+    // Always the first empty square, and always a 4
+    let mut game = Game::<_>::with_rng(StepRng::new(0, 0));
+    let board = |text: &str| text.parse::<Board>().unwrap();
+    assert_eq!(*game.board(), board("4 4 . .\n. . . .\n. . . .\n. . . ."));
+    game.step(Move::Left);
+    assert_eq!(*game.board(), board("8 4 . .\n. . . .\n. . . .\n. . . ."));
+    game.step(Move::Right);
+    assert_eq!(*game.board(), board("4 . 8 4\n. . . .\n. . . .\n. . . ."));
+    assert_eq!(game.score(), 8);
+}
+
+#[test]
+fn a_game_can_carry_on_with_a_different_random_number_generator() {
+    let board = "2 2 . .\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let mut game =
+        Game::from_board(StdRng::seed_from_u64(0), board).replace_rng(StepRng::new(0, 0));
+    game.step(Move::Left);
+    game.step(Move::Right);
+    let expected = "4 . . 8\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    assert_eq!(*game.board(), expected);
+    assert_eq!(game.score(), 4 + 8);
+}
+
+#[test]
+fn the_spawn_label_sums_up_which_tiles_come_and_how_often() {
+    let mut game = Game::<StdRng>::new(StdRng::seed_from_u64(0));
+    assert_eq!(spawn_label(game.spawns()), "spawn: 2/4 90/10");
+    game.set_four_probability(0.25);
+    assert_eq!(spawn_label(game.spawns()), "spawn: 2/4 75/25");
+    let tile = |value| Square::from_value(value).unwrap();
+    // Weights needn't add up to one
+    game.set_spawns(&[(tile(2), 2.0), (tile(4), 1.0), (tile(8), 1.0)]);
+    assert_eq!(spawn_label(game.spawns()), "spawn: 2/4/8 50/25/25");
+}
@@ -0,0 +1,272 @@
+//! Invariants of moving tiles around, checked against many boards that could come up in a real
+//! game. Each case is seeded by its index, so a failure names a board that can be made again.
+
+use play_2048::{fast::Bitboard, simulation::reachable_board, Board, Move, Square};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// How many boards each property is checked against.
+const CASES: u64 = 500;
+
+/// How many moves into a game the boards can be, which is enough to fill most boards.
+const MAX_MOVES: u32 = 300;
+
+/// Check `property` against a board of each case, along with every move that could be made on it.
+fn for_all_boards<const W: usize, const H: usize>(property: impl Fn(Board<W, H>, Move)) {
+    for case in 0..CASES {
+        let mut rng = StdRng::seed_from_u64(case);
+        let moves = (case % u64::from(MAX_MOVES)) as u32;
+        let board = reachable_board(&mut rng, moves);
+        for direction in Move::all() {
+            property(board, direction);
+        }
+    }
+}
+
+fn tile_count<const W: usize, const H: usize>(board: &Board<W, H>) -> usize {
+    board.tiles().count()
+}
+
+#[test]
+fn moving_never_adds_tiles() {
+    for_all_boards::<4, 4>(|board, direction| {
+        let after = board.apply_move(direction);
+        assert!(tile_count(&after) <= tile_count(&board), "{}", board);
+    });
+}
+
+#[test]
+fn moving_keeps_the_sum_of_the_tiles() {
+    for_all_boards::<4, 4>(|board, direction| {
+        let after = board.apply_move(direction);
+        assert_eq!(after.board_sum(), board.board_sum(), "{}", board);
+    });
+}
+
+#[test]
+fn a_new_tile_adds_two_or_four_to_the_sum() {
+    for_all_boards::<4, 4>(|board, direction| {
+        let mut after = board.apply_move(direction);
+        let before = after.board_sum();
+        let mut rng = StdRng::seed_from_u64(u64::from(before));
+        if after.add_square(&mut rng).is_some() {
+            let added = after.board_sum() - before;
+            assert!(added == 2 || added == 4, "{} was added to {}", added, board);
+        }
+    });
+}
+
+#[test]
+fn a_move_without_merges_does_nothing_the_second_time() {
+    for_all_boards::<4, 4>(|board, direction| {
+        let outcome = board.apply_move_detailed(direction);
+        if outcome.merged_tiles == 0 {
+            assert_eq!(
+                outcome.board.apply_move(direction),
+                outcome.board,
+                "{}",
+                board
+            );
+        }
+    });
+}
+
+#[test]
+fn moves_are_deterministic() {
+    for_all_boards::<4, 4>(|board, direction| {
+        let first = board.apply_move_detailed(direction);
+        let second = board.apply_move_detailed(direction);
+        assert_eq!(first.board, second.board, "{}", board);
+        assert_eq!(first.score_gained, second.score_gained, "{}", board);
+    });
+}
+
+#[test]
+fn the_score_goes_up_by_the_merged_tiles() {
+    for_all_boards::<4, 4>(|board, direction| {
+        let outcome = board.apply_move_detailed(direction);
+        assert_eq!(
+            outcome.board.score(),
+            board.score() + outcome.score_gained,
+            "{}",
+            board
+        );
+        assert_eq!(
+            tile_count(&outcome.board) + usize::from(outcome.merged_tiles),
+            tile_count(&board),
+            "{}",
+            board
+        );
+    });
+}
+
+#[test]
+fn rectangular_boards_keep_the_sum_of_the_tiles() {
+    for_all_boards::<3, 5>(|board, direction| {
+        let after = board.apply_move(direction);
+        assert!(tile_count(&after) <= tile_count(&board), "{}", board);
+        assert_eq!(after.board_sum(), board.board_sum(), "{}", board);
+    });
+}
+
+#[test]
+fn heuristic_metrics_are_penalties_that_do_not_care_which_way_the_board_faces() {
+    for_all_boards::<4, 4>(|board, _| {
+        assert!(board.monotonicity() <= 0, "{}", board);
+        assert!(board.smoothness() <= 0, "{}", board);
+        let turned = board.rotate_cw();
+        assert_eq!(turned.monotonicity(), board.monotonicity(), "{}", board);
+        assert_eq!(turned.smoothness(), board.smoothness(), "{}", board);
+    });
+
+    let sorted = "2 4 8 16\n4 8 16 32\n8 16 32 64\n16 32 64 128"
+        .parse::<Board>()
+        .unwrap();
+    assert_eq!(sorted.monotonicity(), 0);
+    assert_eq!(sorted.smoothness(), -24);
+}
+
+/// A board with any tile at all in each cell, or none, including tiles too big to come up in most
+/// games.
+fn any_board(rng: &mut StdRng) -> Board {
+    let mut board = Board::new();
+    for y in 0..4 {
+        for x in 0..4 {
+            let exponent = rng.gen_range(0..=16);
+            board[(x, y)] = (exponent > 0).then(|| Square::from_value(1 << exponent).unwrap());
+        }
+    }
+    board
+}
+
+#[test]
+fn printed_boards_are_read_back_as_they_were() {
+    for_all_boards::<4, 4>(|board, direction| {
+        let board = board.apply_move(direction);
+        assert_eq!(board.to_string().parse::<Board>(), Ok(board), "{}", board);
+    });
+    for case in 0..CASES {
+        let board = any_board(&mut StdRng::seed_from_u64(case));
+        assert_eq!(board.to_string().parse::<Board>(), Ok(board), "{}", board);
+    }
+}
+
+/// The cells of one column or row in the order a move collapses them, as they were walked before
+/// moves were turned into collapses to the left.
+fn coord_iter(direction: Move, offset: usize) -> impl Iterator<Item = (usize, usize)> {
+    let steps = match direction {
+        Move::Up | Move::Left => [0, 1, 2, 3],
+        Move::Down | Move::Right => [3, 2, 1, 0],
+    };
+    steps.into_iter().map(move |i| match direction {
+        Move::Up | Move::Down => (offset, i),
+        Move::Left | Move::Right => (i, offset),
+    })
+}
+
+/// A move made the way it was before, collapsing each column or row straight from the cells in
+/// [coord_iter] order and writing them back in the same order. Gives the board and the score the
+/// move is worth.
+fn reference_move(board: &Board, direction: Move) -> (Board, u32) {
+    let mut output = Board::new();
+    let mut score = 0;
+    for offset in 0..4 {
+        let mut collapsed = Vec::new();
+        let mut last_seen = None;
+        for tile in coord_iter(direction, offset).filter_map(|cell| board[cell]) {
+            if last_seen == Some(tile) {
+                let merged = tile.inc();
+                score += merged.value();
+                collapsed.pop();
+                collapsed.push(merged);
+                last_seen = None;
+            } else {
+                collapsed.push(tile);
+                last_seen = Some(tile);
+            }
+        }
+        for (cell, tile) in coord_iter(direction, offset).zip(collapsed) {
+            output[cell] = Some(tile);
+        }
+    }
+    (output, score)
+}
+
+#[test]
+fn moves_are_the_same_as_walking_each_lane_in_order() {
+    let check = |board: Board, direction| {
+        let (expected, score) = reference_move(&board, direction);
+        let moved = board.apply_move(direction);
+        assert_eq!(moved, expected, "{:?}\n{}", direction, board);
+        assert_eq!(
+            moved.score() - board.score(),
+            score,
+            "{:?}\n{}",
+            direction,
+            board
+        );
+    };
+    for_all_boards::<4, 4>(check);
+    for case in 0..CASES {
+        let board = any_board(&mut StdRng::seed_from_u64(case));
+        for direction in Move::all() {
+            check(board, direction);
+        }
+    }
+}
+
+/// Check both of the fast ways to move against [Board::apply_move_detailed]: the bitboard, for
+/// boards whose tiles fit in it, and [Board::apply_move_fast], for any board at all.
+fn check_fast_moves(board: Board, direction: Move) {
+    let expected = board.apply_move_detailed(direction);
+
+    let fast = board.apply_move_fast(direction);
+    assert!(
+        fast.same_state(&expected.board),
+        "{:?}\n{}",
+        direction,
+        board
+    );
+
+    // Two 32768s merge on a board, but a bitboard can't hold what they make, so it won't take them
+    let Ok(bitboard) = Bitboard::try_from(board) else {
+        assert!(board.max_tile() >= Square::from_value(1 << 15), "{}", board);
+        return;
+    };
+    {
+        let (moved, score) = bitboard.apply_move_with_score(direction);
+        assert_eq!(
+            Board::from(moved),
+            expected.board,
+            "{:?}\n{}",
+            direction,
+            board
+        );
+        assert_eq!(score, expected.score_gained, "{:?}\n{}", direction, board);
+        assert_eq!(
+            moved != bitboard,
+            expected.moved,
+            "{:?}\n{}",
+            direction,
+            board
+        );
+    }
+}
+
+#[test]
+fn fast_moves_are_the_same_as_the_usual_ones() {
+    for_all_boards::<4, 4>(check_fast_moves);
+    for case in 0..CASES {
+        let board = any_board(&mut StdRng::seed_from_u64(case));
+        for direction in Move::all() {
+            check_fast_moves(board, direction);
+        }
+    }
+}
+
+#[test]
+fn the_opposite_of_the_opposite_is_the_same_move() {
+    for direction in Move::all() {
+        assert_ne!(direction.opposite(), direction);
+        assert_eq!(direction.opposite().opposite(), direction);
+    }
+}
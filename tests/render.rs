@@ -0,0 +1,368 @@
+#![cfg(feature = "tui")]
+
+use crossterm::style::Color;
+use play_2048::{
+    render_to_string, Animation, Backend, Board, Borders, Justify, Move, Renderer, Stats,
+    StringBackend, Theme, TileStyle,
+};
+use std::{io, time::Duration};
+
+fn board(text: &str) -> Board {
+    text.parse().unwrap()
+}
+
+#[test]
+fn the_board_is_drawn_as_render_to_string_lays_it_out() {
+    let board = board("2 . . 4\n. 8 . .\n. . 16 .\n2048 . . .");
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::classic()).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+
+    let text = renderer.backend().text();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("Score: 0  Sum: 0  Best: 0  Moves: 0"));
+    for expected in render_to_string(&board).lines() {
+        assert_eq!(lines.next(), Some(expected.trim_end()));
+    }
+    // The message line below is left empty
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn only_the_changes_are_needed_to_redraw() {
+    let before = board("2 2 . .\n. . . .\n. . . .\n. . . 4");
+    let after = board("4 . . .\n. . . .\n. . . .\n. . . 4");
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    renderer.set_borders(Borders::Ascii).unwrap();
+    renderer.draw_board(&before, &Stats::default()).unwrap();
+    renderer.draw_board(&after, &Stats::default()).unwrap();
+
+    let mut fresh = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    fresh.set_borders(Borders::Ascii).unwrap();
+    fresh.draw_board(&after, &Stats::default()).unwrap();
+    assert_eq!(renderer.backend().text(), fresh.backend().text());
+}
+
+#[test]
+fn justification_is_the_same_when_only_the_changes_are_redrawn() {
+    let before = board("2 2 . .\n. . . .\n. . . .\n. . . 128");
+    let after = board("4 . . .\n. . . .\n. . . .\n. . . 128");
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    renderer.set_justify(Justify::Center);
+    renderer.draw_board(&before, &Stats::default()).unwrap();
+    renderer.draw_board(&after, &Stats::default()).unwrap();
+
+    let text = renderer.backend().text();
+    assert_eq!(text.lines().nth(1), Some("  4"));
+    assert_eq!(text.lines().nth(4), Some("                128"));
+}
+
+#[test]
+fn the_end_screen_takes_the_place_of_the_board_until_it_is_drawn_again() {
+    let board = board("2 4 2 4\n4 2 4 2\n2 4 2 4\n4 2 4 2");
+    let stats = Stats {
+        score: 120,
+        moves: 30,
+        ..Stats::default()
+    };
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    renderer.draw_board(&board, &stats).unwrap();
+    renderer.end_screen("Game over", &stats, 4).unwrap();
+
+    let text = renderer.backend().text();
+    let lines = text.lines().skip(1).map(str::trim).collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "Game over",
+            "Score: 120  Best: 0",
+            "Highest tile: 4  Moves: 30",
+            "",
+            "Press r to restart or Esc to quit",
+        ]
+    );
+
+    renderer.message("").unwrap();
+    renderer.draw_board(&board, &stats).unwrap();
+    let mut fresh = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    fresh.draw_board(&board, &stats).unwrap();
+    assert_eq!(renderer.backend().text(), fresh.backend().text());
+}
+
+#[test]
+fn an_animated_move_ends_on_the_board_it_led_to() {
+    let before = board("2 2 . .\n. . . .\n. . . .\n. . . 4");
+    let after = board("4 . . .\n. . . .\n. . 2 .\n4 . . .");
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    renderer.set_animation(Some(Animation {
+        frames: 2,
+        frame_time: Duration::ZERO,
+    }));
+    renderer.draw_board(&before, &Stats::default()).unwrap();
+    renderer
+        .draw_board_animated(&after, &Stats::default(), Move::Left, Some((2, 2)))
+        .unwrap();
+
+    let mut fresh = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    fresh.draw_board(&after, &Stats::default()).unwrap();
+    assert_eq!(renderer.backend().text(), fresh.backend().text());
+}
+
+#[test]
+fn big_numbers_are_drawn_when_there_is_room_for_them() {
+    let board = board("2 . . .\n. . . .\n. . . .\n. . . 2048");
+    let mut renderer = Renderer::with_backend(StringBackend::new(60), Theme::mono()).unwrap();
+    renderer.set_big(true).unwrap();
+    renderer.resize((60, 20)).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+
+    let text = renderer.backend().text();
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines[1], "           _");
+    assert_eq!(lines[2], "           _|");
+    assert_eq!(lines[3], "          |_");
+    assert_eq!(
+        lines[10],
+        "                                            _  _     _"
+    );
+    assert_eq!(
+        lines[11],
+        "                                            _|| ||_||_|"
+    );
+    assert_eq!(
+        lines[12],
+        "                                           |_ |_|  ||_|"
+    );
+
+    // Too short for big numbers, so the usual ones are back
+    renderer.resize((60, 10)).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+    let text = renderer.backend().text();
+    let lines = text.lines().skip(1).take(4).collect::<Vec<_>>();
+    let expected = render_to_string(&board);
+    assert_eq!(
+        lines,
+        expected.lines().map(str::trim_end).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn narrow_windows_get_narrower_squares_before_being_called_too_small() {
+    let board = board("2 . . 4\n. 1024 . .\n. . 16 .\n65536 . . .");
+    let mut renderer = Renderer::with_backend(StringBackend::new(14), Theme::mono()).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+    let text = renderer.backend().text();
+    assert_eq!(text.lines().nth(2), Some("    1k"));
+    assert_eq!(text.lines().nth(4), Some("64k"));
+
+    renderer.resize((9, 6)).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+    let text = renderer.backend().text();
+    let lines = text.lines().skip(1).collect::<Vec<_>>();
+    assert_eq!(lines, [" 0     1", "   9", "     3", " f"]);
+
+    renderer.resize((7, 6)).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+    // Only the start of the note fits
+    assert!(renderer.backend().text().contains("Termina"));
+}
+
+#[test]
+fn the_cursor_is_parked_below_the_board_only_when_asked() {
+    let board = board("2 . . .\n. . . .\n. . . .\n. . . 4");
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+    assert_eq!(renderer.backend().cursor(), None);
+
+    renderer.set_park_cursor(true);
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+    // The header, then four rows of squares
+    assert_eq!(renderer.backend().cursor(), Some((5, 0)));
+}
+
+#[test]
+fn tiles_past_65536_fit_in_their_squares() {
+    // Two 65536s merge into a tile too long for its square
+    let board = board("65536 65536 . 2\n. . . .\n. . . .\n. . . .").apply_move(Move::Left);
+    assert_eq!(board.max_tile().map(|tile| tile.value()), Some(131072));
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::classic()).unwrap();
+    renderer.draw_board(&board, &Stats::default()).unwrap();
+    // 131072 is too long for a five column square, so it's shortened to fit, leaving the 2 in
+    // the square after it where it belongs
+    let text = renderer.backend().text();
+    assert_eq!(text.lines().nth(1), Some(" 128k    2"));
+}
+
+#[test]
+fn the_end_screen_and_too_small_note_stay_inside_a_tiny_screen() {
+    let board = board("2 4 2 4\n4 2 4 2\n2 4 2 4\n4 2 4 2");
+    let stats = Stats {
+        score: 120,
+        moves: 30,
+        ..Stats::default()
+    };
+    let mut renderer = Renderer::with_backend(StringBackend::new(12), Theme::mono()).unwrap();
+    renderer.draw_board(&board, &stats).unwrap();
+    renderer.end_screen("Game over", &stats, 4).unwrap();
+    // The lines wider than the screen are cut short rather than drawn past its edge
+    let text = renderer.backend().text();
+    assert_eq!(text.lines().nth(1), Some(" Game over"));
+    assert_eq!(text.lines().nth(3), Some("Highest tile"));
+    assert!(!renderer.backend().drew_off_screen());
+
+    // Too small for even the narrowest squares, so the note takes the place of the banner
+    renderer.resize((7, 6)).unwrap();
+    renderer.draw_board(&board, &stats).unwrap();
+    renderer.end_screen("Game over", &stats, 4).unwrap();
+    let text = renderer.backend().text();
+    assert!(text.contains("Termina"));
+    assert!(!text.contains("Game over"));
+    assert!(!renderer.backend().drew_off_screen());
+}
+
+#[test]
+fn drawing_from_scratch_covers_whatever_was_on_the_screen() {
+    let board = board("2 . . 4\n. 8 . .\n. . 16 .\n2048 . . .");
+    let stats = Stats::default();
+    let mut fresh = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    fresh.draw_board(&board, &stats).unwrap();
+
+    let scribble = |renderer: &mut Renderer<StringBackend>| {
+        // Everything but the message row, which the board leaves alone
+        for row in 0..5 {
+            renderer
+                .backend_mut()
+                .draw_text(row, 0, &"#".repeat(40))
+                .unwrap();
+        }
+    };
+    let mut renderer = Renderer::with_backend(StringBackend::new(40), Theme::mono()).unwrap();
+    renderer.draw_board(&board, &stats).unwrap();
+    scribble(&mut renderer);
+    renderer.force_redraw(&board, &stats).unwrap();
+    assert_eq!(renderer.backend().text(), fresh.backend().text());
+
+    // A resize draws the board from scratch without blanking it first, so the empty squares have
+    // to be written over too
+    scribble(&mut renderer);
+    renderer.resize((40, 6)).unwrap();
+    renderer.draw_board(&board, &stats).unwrap();
+    assert_eq!(renderer.backend().text(), fresh.backend().text());
+}
+
+#[test]
+fn the_spawn_label_goes_in_the_header() {
+    let board = board("2 . . .\n. . . .\n. . . .\n. . . 4");
+    let stats = Stats {
+        spawns: Some("spawn: 2/4 75/25".to_string()),
+        ..Stats::default()
+    };
+    let mut renderer = Renderer::with_backend(StringBackend::new(60), Theme::mono()).unwrap();
+    renderer.draw_board(&board, &stats).unwrap();
+    assert_eq!(
+        renderer.backend().text().lines().next(),
+        Some("Score: 0  Sum: 0  Best: 0  Moves: 0  spawn: 2/4 75/25")
+    );
+}
+
+/// A string backend that also keeps the style of every tile drawn on it, in order.
+struct StyleBackend {
+    text: StringBackend,
+    styles: Vec<TileStyle>,
+}
+
+impl Backend for StyleBackend {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        self.text.size()
+    }
+
+    fn reserve_rows(&mut self, rows: u16) -> io::Result<()> {
+        self.text.reserve_rows(rows)
+    }
+
+    fn clear_screen(&mut self) -> io::Result<()> {
+        self.text.clear_screen()
+    }
+
+    fn clear_line(&mut self, row: u16, column: u16) -> io::Result<()> {
+        self.text.clear_line(row, column)
+    }
+
+    fn draw_text(&mut self, row: u16, column: u16, text: &str) -> io::Result<()> {
+        self.text.draw_text(row, column, text)
+    }
+
+    fn draw_cell(&mut self, row: u16, column: u16, text: &str, style: TileStyle) -> io::Result<()> {
+        self.styles.push(style);
+        self.text.draw_cell(row, column, text, style)
+    }
+
+    fn park_cursor(&mut self, row: u16, column: u16) -> io::Result<()> {
+        self.text.park_cursor(row, column)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.text.flush()
+    }
+}
+
+#[test]
+fn merged_tiles_fade_through_the_colours_between_their_tiers() {
+    let before = board("8 8 . .\n. . . .\n. . . .\n. . . .");
+    let after = before.apply_move(Move::Left);
+    let backgrounds = |fade| {
+        let backend = StyleBackend {
+            text: StringBackend::new(40),
+            styles: Vec::new(),
+        };
+        let mut renderer = Renderer::with_backend(backend, Theme::classic()).unwrap();
+        renderer.set_animation(Some(Animation {
+            frames: 2,
+            frame_time: Duration::ZERO,
+        }));
+        renderer.set_fade(fade);
+        renderer.draw_board(&before, &Stats::default()).unwrap();
+        renderer.backend_mut().styles.clear();
+        renderer
+            .draw_board_animated(&after, &Stats::default(), Move::Left, None)
+            .unwrap();
+        renderer
+            .backend()
+            .styles
+            .iter()
+            .filter_map(|style| style.background)
+            .collect::<Vec<_>>()
+    };
+
+    // From 8's colours towards 16's, a third of the way and then two thirds
+    let between = [
+        Color::Rgb {
+            r: 243,
+            g: 168,
+            b: 114,
+        },
+        Color::Rgb {
+            r: 244,
+            g: 159,
+            b: 107,
+        },
+    ];
+    let faded = backgrounds(true);
+    let fading = faded
+        .iter()
+        .filter(|colour| between.contains(colour))
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(fading, between);
+    // The fade ends on 16's own colours
+    assert_eq!(
+        faded.last(),
+        Some(&Color::Rgb {
+            r: 245,
+            g: 149,
+            b: 99
+        })
+    );
+    assert!(!backgrounds(false)
+        .iter()
+        .any(|colour| between.contains(colour)));
+}
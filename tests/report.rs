@@ -0,0 +1,60 @@
+use play_2048::{report::Report, Game, Move};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    fs,
+    time::{Duration, UNIX_EPOCH},
+};
+
+#[test]
+fn a_report_holds_the_board_and_score_in_a_file_named_after_when_it_was_made() {
+    let directory = std::env::temp_dir().join(format!("2048-report-test-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let mut game = Game::<StdRng>::new(StdRng::seed_from_u64(3));
+    for direction in [Move::Left, Move::Up, Move::Right, Move::Down, Move::Left] {
+        game.step(direction);
+    }
+    let report = Report {
+        seed: Some(3),
+        mode: "classic".to_string(),
+        board: *game.board(),
+        moves: game.history().to_vec(),
+        terminal_size: Some((80, 24)),
+        // Half past one in the afternoon, on the 15th of October 2026
+        time: UNIX_EPOCH + Duration::from_secs(1_792_071_000),
+    };
+
+    let path = report.write_in(&directory).unwrap();
+    assert_eq!(path, directory.join("2048-report-1792071000.txt"));
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines = contents.lines().collect::<Vec<_>>();
+    assert!(lines.contains(&"time: 2026-10-15 13:30:00 UTC"));
+    assert!(lines.contains(&"seed: 3"));
+    assert!(lines.contains(&"terminal: 80x24"));
+    assert!(lines.contains(&format!("board: {}", game.board().to_compact()).as_str()));
+    assert!(lines.contains(&format!("score: {}", game.score()).as_str()));
+    fs::remove_dir_all(&directory).ok();
+}
+
+#[test]
+fn reports_made_in_the_same_second_go_to_different_files() {
+    let directory =
+        std::env::temp_dir().join(format!("2048-report-same-test-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let game = Game::<StdRng>::new(StdRng::seed_from_u64(4));
+    let report = Report {
+        seed: Some(4),
+        mode: "classic".to_string(),
+        board: *game.board(),
+        moves: Vec::new(),
+        terminal_size: None,
+        time: UNIX_EPOCH + Duration::from_secs(1_792_071_000),
+    };
+
+    let first = report.write_in(&directory).unwrap();
+    let second = report.write_in(&directory).unwrap();
+    let third = report.write_in(&directory).unwrap();
+    assert_eq!(first, directory.join("2048-report-1792071000.txt"));
+    assert_eq!(second, directory.join("2048-report-1792071000-2.txt"));
+    assert_eq!(third, directory.join("2048-report-1792071000-3.txt"));
+    fs::remove_dir_all(&directory).ok();
+}
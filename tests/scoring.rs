@@ -0,0 +1,29 @@
+use play_2048::{scoring::Combo, Board, Game, Move};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// A board where moving left makes three merges, of a 4, an 8 and then a 16.
+fn three_merges() -> Game<StdRng> {
+    let board = "2 2 4 4\n8 8 . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let mut game = Game::from_board(StdRng::seed_from_u64(0), board);
+    game.set_auto_spawn(false);
+    game
+}
+
+#[test]
+fn merges_add_their_tiles_by_default() {
+    let mut game = three_merges();
+    game.step(Move::Left);
+    assert_eq!(game.score(), 4 + 8 + 16);
+    assert_eq!(game.last_multiplier(), 1);
+}
+
+#[test]
+fn combos_multiply_each_merge_by_how_many_came_before() {
+    let mut game = three_merges();
+    game.set_scoring_rule(Box::new(Combo));
+    game.step(Move::Left);
+    assert_eq!(game.score(), 4 + 8 * 2 + 16 * 3);
+    assert_eq!(game.last_multiplier(), 3);
+}
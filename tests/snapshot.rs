@@ -0,0 +1,190 @@
+//! Snapshots are JSON written by hand, so these read them back with a strict parser to check that
+//! what comes out is really JSON, and holds the game it was taken of.
+
+use play_2048::{snapshot::Snapshot, Board, Game, Move};
+use rand::{rngs::StdRng, SeedableRng};
+use std::iter::Peekable;
+
+/// A JSON value, with numbers kept as they were written.
+#[derive(Clone, Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+/// Read a whole JSON document, as laid out in RFC 8259, or panic saying where it went wrong.
+fn parse(text: &str) -> Json {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars);
+    skip_whitespace(&mut chars);
+    assert_eq!(chars.next(), None, "trailing characters in {}", text);
+    value
+}
+
+fn skip_whitespace(chars: &mut Peekable<impl Iterator<Item = char>>) {
+    while chars
+        .next_if(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        .is_some()
+    {}
+}
+
+fn expect(chars: &mut Peekable<impl Iterator<Item = char>>, word: &str) {
+    for expected in word.chars() {
+        assert_eq!(chars.next(), Some(expected), "expected {}", word);
+    }
+}
+
+fn parse_value(chars: &mut Peekable<impl Iterator<Item = char>>) -> Json {
+    skip_whitespace(chars);
+    let value = match chars.peek().copied() {
+        Some('n') => {
+            expect(chars, "null");
+            Json::Null
+        }
+        Some('t') => {
+            expect(chars, "true");
+            Json::Bool(true)
+        }
+        Some('f') => {
+            expect(chars, "false");
+            Json::Bool(false)
+        }
+        Some('"') => Json::String(parse_string(chars)),
+        Some('[') => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_none() {
+                loop {
+                    items.push(parse_value(chars));
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some(']') => break,
+                        other => panic!("expected , or ] in an array, found {:?}", other),
+                    }
+                }
+            }
+            Json::Array(items)
+        }
+        Some('{') => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&'}').is_none() {
+                loop {
+                    skip_whitespace(chars);
+                    let name = parse_string(chars);
+                    skip_whitespace(chars);
+                    expect(chars, ":");
+                    fields.push((name, parse_value(chars)));
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some('}') => break,
+                        other => panic!("expected , or }} in an object, found {:?}", other),
+                    }
+                }
+            }
+            Json::Object(fields)
+        }
+        Some('-' | '0'..='9') => {
+            let mut number = String::new();
+            if let Some(minus) = chars.next_if_eq(&'-') {
+                number.push(minus);
+            }
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                number.push(digit);
+            }
+            // Fractions and exponents never come up in a snapshot, so they're not read here
+            let digits = number.trim_start_matches('-');
+            assert!(
+                digits == "0" || (!digits.is_empty() && !digits.starts_with('0')),
+                "{:?} isn't a number",
+                number
+            );
+            Json::Number(number)
+        }
+        other => panic!("expected a value, found {:?}", other),
+    };
+    skip_whitespace(chars);
+    value
+}
+
+fn parse_string(chars: &mut Peekable<impl Iterator<Item = char>>) -> String {
+    expect(chars, "\"");
+    let mut string = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return string,
+            Some('\\') => match chars.next() {
+                Some(escaped @ ('"' | '\\' | '/')) => string.push(escaped),
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                other => panic!("unexpected escape {:?}", other),
+            },
+            Some(c) if c >= ' ' => string.push(c),
+            other => panic!("unexpected {:?} in a string", other),
+        }
+    }
+}
+
+/// The JSON a snapshot of `board` with the given score and moves should come out as.
+fn expected<const W: usize, const H: usize>(
+    board: &Board<W, H>,
+    score: u32,
+    moves: u32,
+    game_over: bool,
+) -> Json {
+    let rows = (0..H)
+        .map(|y| {
+            let cells = (0..W)
+                .map(|x| match board[(x, y)] {
+                    Some(square) => Json::Number(square.value().to_string()),
+                    None => Json::Null,
+                })
+                .collect();
+            Json::Array(cells)
+        })
+        .collect();
+    Json::Object(vec![
+        ("board".to_string(), Json::Array(rows)),
+        ("score".to_string(), Json::Number(score.to_string())),
+        ("moves".to_string(), Json::Number(moves.to_string())),
+        ("game_over".to_string(), Json::Bool(game_over)),
+    ])
+}
+
+#[test]
+fn snapshots_are_json_holding_the_game() {
+    let mut game = Game::<StdRng>::new(StdRng::seed_from_u64(5));
+    for direction in [Move::Left, Move::Up, Move::Right, Move::Down].repeat(5) {
+        let snapshot = Snapshot::of(&game);
+        assert_eq!(
+            parse(&snapshot.to_string()),
+            expected(game.board(), game.score(), game.moves_made(), false)
+        );
+        game.step(direction);
+    }
+}
+
+#[test]
+fn snapshots_of_lost_and_empty_boards_are_json_too() {
+    let lost = "2 4 2 4\n4 2 4 2\n2 4 2 4\n4 2 4 131072"
+        .parse::<Board>()
+        .unwrap();
+    let game = Game::from_board(StdRng::seed_from_u64(6), lost);
+    assert_eq!(
+        parse(&Snapshot::of(&game).to_string()),
+        expected(&lost, 0, 0, true)
+    );
+
+    let empty = Board::<3, 2>::new();
+    let game = Game::from_board(StdRng::seed_from_u64(7), empty);
+    assert_eq!(
+        parse(&Snapshot::of(&game).to_string()),
+        expected(&empty, 0, 0, false)
+    );
+}
@@ -0,0 +1,47 @@
+use play_2048::spectate::{Mode, ParseSpectateCodeError, SpectateCode};
+
+#[test]
+fn codes_are_read_back_as_they_were_written() {
+    for (seed, mode, width, height) in [
+        (0, Mode::Classic, 4, 4),
+        (42, Mode::Combo, 4, 4),
+        (u64::MAX, Mode::Classic, 4, 4),
+    ] {
+        let code = SpectateCode {
+            seed,
+            mode,
+            width,
+            height,
+        };
+        assert_eq!(code.to_string().parse(), Ok(code));
+    }
+    let code = SpectateCode {
+        seed: 42,
+        mode: Mode::Combo,
+        width: 4,
+        height: 4,
+    };
+    assert_eq!(code.to_string(), "combo-4x4-2a");
+    assert_eq!(" Combo-4X4-2A ".parse(), Ok(code));
+}
+
+#[test]
+fn anything_else_is_not_a_code() {
+    for text in [
+        "",
+        "classic-4x4",
+        "classic-4x4-2a-1",
+        "blitz-4x4-2a",
+        "classic-4-2a",
+        "classic-0x4-2a",
+        // Codes that read must be games that can be played, which only 4x4 ones can
+        "classic-5x3-2a",
+        "combo-3x3-2a",
+        "classic-4x4-seed",
+    ] {
+        assert_eq!(
+            text.parse::<SpectateCode>(),
+            Err(ParseSpectateCodeError(text.to_string()))
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use play_2048::{Board, Move};
+
+#[test]
+fn every_tile_is_printed_apart_from_its_neighbours_and_read_back() {
+    let board = "2 4 8 16\n32 64 128 256\n512 1024 2048 4096\n8192 16384 32768 65536"
+        .parse::<Board>()
+        .unwrap();
+    let text = board.to_string();
+    assert_eq!(
+        text,
+        [
+            "     2     4     8    16",
+            "    32    64   128   256",
+            "   512  1024  2048  4096",
+            "  8192 16384 32768 65536",
+        ]
+        .join("\n")
+    );
+    assert!(text.parse::<Board>().unwrap().same_state(&board));
+
+    let sparse = "2 16384 . .\n. . . .\n. . . .\n. . . 65536"
+        .parse::<Board>()
+        .unwrap();
+    assert_eq!(
+        sparse.to_string().lines().next(),
+        Some("     2 16384     .     .")
+    );
+    assert_eq!(sparse.to_string().parse::<Board>(), Ok(sparse));
+}
+
+#[test]
+fn moves_are_explained_lane_by_lane() {
+    let board = "4 4 2 2\n. 2 . .\n2 4 8 16\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    assert_eq!(
+        board.explain_move(Move::Left),
+        [
+            "Left row 1: [4,4,2,2] → [8,4]: merged 4+4→8 and 2+2→4",
+            "Left row 2: [.,2,.,.] → [2]: slid",
+        ]
+    );
+    assert_eq!(
+        board.explain_move(Move::Right),
+        [
+            "Right row 1: [4,4,2,2] → [8,4]: merged 2+2→4 and 4+4→8",
+            "Right row 2: [.,2,.,.] → [2]: slid",
+        ]
+    );
+    // Nothing in the way can slide or merge, so there's nothing to explain
+    let blocked = "2 4 8 16\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    assert!(blocked.explain_move(Move::Left).is_empty());
+    assert!(blocked.explain_move(Move::Up).is_empty());
+}
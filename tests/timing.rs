@@ -0,0 +1,110 @@
+//! Pacing the boards on the screen and timing games against the clock, with made-up times so that
+//! nothing has to wait for real.
+
+use play_2048::{Board, Game, Move, Pacer};
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::{Duration, Instant};
+
+const MIN_FRAME_TIME: Duration = Duration::from_millis(30);
+
+/// Drain a queue of moves the way the game does: wait out the pacer, make the move, then spend
+/// `draw_time` drawing it. Gives when each board went on the screen.
+fn drain(draw_times: &[Duration]) -> Vec<Instant> {
+    let mut pacer = Pacer::new(MIN_FRAME_TIME);
+    let mut now = Instant::now();
+    pacer.frame_shown(now);
+    let mut shown = vec![now];
+    for &draw_time in draw_times {
+        now += pacer.remaining(now);
+        now += draw_time;
+        pacer.frame_shown(now);
+        shown.push(now);
+    }
+    shown
+}
+
+#[test]
+fn every_board_stays_up_for_the_minimum_frame_time() {
+    let shown = drain(&[Duration::from_millis(2); 5]);
+    for pair in shown.windows(2) {
+        assert_eq!(pair[1] - pair[0], MIN_FRAME_TIME + Duration::from_millis(2));
+    }
+}
+
+#[test]
+fn a_board_that_was_slow_to_draw_holds_up_the_next_move_no_longer() {
+    let slow = MIN_FRAME_TIME * 2;
+    let shown = drain(&[slow, Duration::ZERO]);
+    assert_eq!(shown[1] - shown[0], MIN_FRAME_TIME + slow);
+    assert_eq!(shown[2] - shown[1], MIN_FRAME_TIME);
+
+    let mut pacer = Pacer::new(MIN_FRAME_TIME);
+    let now = Instant::now();
+    assert_eq!(pacer.remaining(now), Duration::ZERO);
+    pacer.frame_shown(now);
+    assert_eq!(
+        pacer.remaining(now + Duration::from_millis(10)),
+        Duration::from_millis(20)
+    );
+    assert_eq!(pacer.remaining(now + slow), Duration::ZERO);
+}
+
+#[test]
+fn time_runs_out_at_the_limit_counting_only_while_the_clock_runs() {
+    let limit = Duration::from_secs(180);
+    let mut game = Game::<StdRng>::new(StdRng::seed_from_u64(0));
+    let start = Instant::now();
+    game.clock_mut().start(start);
+    assert!(!game.out_of_time(start + limit * 2), "no limit, no end");
+
+    game.set_time_limit(Some(limit));
+    assert!(!game.out_of_time(start));
+    assert!(!game.out_of_time(start + limit - Duration::from_millis(1)));
+    assert!(game.out_of_time(start + limit));
+
+    // A minute with the clock stopped doesn't count
+    game.clock_mut().stop(start + Duration::from_secs(60));
+    game.clock_mut().start(start + Duration::from_secs(120));
+    assert!(!game.out_of_time(start + limit));
+    assert!(game.out_of_time(start + limit + Duration::from_secs(60)));
+}
+
+#[test]
+fn a_move_under_way_as_time_runs_out_is_finished() {
+    let board = "2 2 . .\n. . . .\n. . . .\n. . . ."
+        .parse::<Board>()
+        .unwrap();
+    let mut game = Game::from_board(StdRng::seed_from_u64(0), board);
+    let start = Instant::now();
+    game.set_time_limit(Some(Duration::from_secs(1)));
+    game.clock_mut().start(start);
+    assert!(!game.out_of_time(start));
+    // The time is up by the time the move is made, but it still goes through
+    let result = game.step(Move::Left);
+    assert!(result.moved);
+    assert_eq!(game.score(), 4);
+    assert!(game.out_of_time(start + Duration::from_secs(1)));
+}
+
+#[test]
+#[cfg(feature = "tui")]
+fn held_keys_play_no_faster_than_the_repeat_interval() {
+    use play_2048::keys::KeyRepeat;
+
+    let interval = Duration::from_millis(80);
+    let mut repeat = KeyRepeat::new(interval);
+    let start = Instant::now();
+    let at = |millis| start + Duration::from_millis(millis);
+    repeat.press(start);
+    // The terminal repeats every 30ms, but a move only goes through every 80ms
+    let played = (1..=10)
+        .filter(|&step| repeat.repeat(at(30 * step)))
+        .map(|step| 30 * step)
+        .collect::<Vec<_>>();
+    assert_eq!(played, [90, 180, 270]);
+
+    // Pressing the key again always counts, and holding it starts over from there
+    repeat.press(at(310));
+    assert!(!repeat.repeat(at(350)));
+    assert!(repeat.repeat(at(390)));
+}